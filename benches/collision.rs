@@ -0,0 +1,103 @@
+use std::f32::consts::TAU;
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use num_complex::Complex;
+use touhou_unfinished_matrix_dream::{
+    cmpx,
+    components::{
+        Bullet, CircleHitbox, Damage, DieOffScreen, MoveParams, Player, PreviousPosition, Sprite,
+        Transform2D,
+    },
+    controls::ActionState,
+    entity::{create_enemy_bullet, miko, reimu_a},
+    event::EventManager,
+    math::ComplexExt,
+    score::ScoreData,
+    simulation::{Simulation, PLAY_FIELD},
+    stage::script::BossKind,
+    systems::update_collision,
+    vec2,
+};
+
+const BULLET_COUNTS: [usize; 3] = [1_000, 5_000, 10_000];
+
+// INFO : A stage frozen in the middle of a dense pattern, enemy bullets are spread over the
+// whole field but keep out of the player hitbox so the whole tick is measured, not a death
+fn dense_simulation(bullets: usize) -> Simulation {
+    let mut sim = Simulation::new(EventManager::default(), ScoreData::default(), 0x5eed);
+    reimu_a(&mut sim.world);
+    let pattern = BossKind::Miko
+        .pattern()
+        .expect("[-] Failed to load boss pattern!");
+    miko(&mut sim.world, &pattern, sim.score.difficulty);
+
+    let player = cmpx!(150., 400.);
+    let mut spawned = 0;
+    while spawned < bullets {
+        let position = cmpx!(
+            sim.rng.range_f32(0., PLAY_FIELD.0),
+            sim.rng.range_f32(0., PLAY_FIELD.1)
+        );
+        if position.distance_squared(&player) < 40. * 40. {
+            continue;
+        }
+
+        let velocity = Complex::cdir(sim.rng.next_f32() * TAU) * 60.;
+        create_enemy_bullet(
+            &mut sim.world,
+            Transform2D::new(position, vec2!(1.), 0.),
+            Sprite::new("commons_sprite", 0, 1, 32., 32.),
+            MoveParams::move_linear(velocity),
+            CircleHitbox::new(4., vec2!(0.)),
+        );
+        spawned += 1;
+    }
+
+    for i in 0..40 {
+        let position = cmpx!(130. + (i % 4) as f32 * 12., 380. - (i / 4) as f32 * 36.);
+        sim.world.spawn((
+            Player,
+            Bullet,
+            DieOffScreen,
+            Transform2D::new(position, vec2!(1.), 0.),
+            PreviousPosition(position),
+            Damage(10.),
+            MoveParams::move_linear(cmpx!(0., -2000.)),
+            CircleHitbox::new(2., vec2!(0.)),
+        ));
+    }
+
+    sim
+}
+
+fn collision(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_collision");
+    for bullets in BULLET_COUNTS {
+        group.bench_with_input(BenchmarkId::from_parameter(bullets), &bullets, |b, &n| {
+            b.iter_batched_ref(
+                || dense_simulation(n),
+                |sim| update_collision(black_box(sim)),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn step(c: &mut Criterion) {
+    let input = ActionState::new(0, 0);
+    let mut group = c.benchmark_group("simulation_step");
+    for bullets in BULLET_COUNTS {
+        group.bench_with_input(BenchmarkId::from_parameter(bullets), &bullets, |b, &n| {
+            b.iter_batched_ref(
+                || dense_simulation(n),
+                |sim| sim.step(black_box(&input)),
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, collision, step);
+criterion_main!(benches);
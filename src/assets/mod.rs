@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use bgm::BGM;
 use raylib::prelude::*;
-use sfx::Sfx;
+use sfx::{Sfx, SoundEffect};
 
 pub mod bgm;
 pub mod sfx;
@@ -24,6 +24,9 @@ pub struct AudioAssets<'a> {
     pub generic_shoot: Sfx<'a>,
     pub shot1: Sfx<'a>,
     pub spell_end: Sfx<'a>,
+    pub graze: Sfx<'a>,
+    pub enemy_death: Sfx<'a>,
+    pub bomb: Sfx<'a>,
 }
 
 impl<'a> AudioAssets<'a> {
@@ -135,6 +138,9 @@ impl<'a> AudioAssets<'a> {
         let shot1 = Sfx::new("./assets/sfx/shot1.ogg", audio);
         let generic_shoot = Sfx::new("./assets/sfx/generic_shoot.ogg", audio);
         let spell_end = Sfx::new("./assets/sfx/spell_end.ogg", audio);
+        let graze = Sfx::new("./assets/sfx/graze.wav", audio);
+        let enemy_death = Sfx::new("./assets/sfx/enemy_death.wav", audio);
+        let bomb = Sfx::new("./assets/sfx/bomb.wav", audio);
 
         Self {
             select_sfx,
@@ -143,6 +149,9 @@ impl<'a> AudioAssets<'a> {
             generic_shoot,
             shot1,
             spell_end,
+            graze,
+            enemy_death,
+            bomb,
             current_played_bgm: None,
         }
     }
@@ -153,6 +162,19 @@ impl<'a> AudioAssets<'a> {
         self.bgm[id].play_stream(vol);
     }
 
+    pub fn play_sfx(&mut self, sfx: SoundEffect, vol: f32) {
+        match sfx {
+            SoundEffect::Select => self.select_sfx.play(vol),
+            SoundEffect::Death => self.death_sfx.play(vol),
+            SoundEffect::GenericShoot => self.generic_shoot.play(vol),
+            SoundEffect::Shot1 => self.shot1.play(vol),
+            SoundEffect::SpellEnd => self.spell_end.play(vol),
+            SoundEffect::Graze => self.graze.play(vol),
+            SoundEffect::EnemyDeath => self.enemy_death.play(vol),
+            SoundEffect::Bomb => self.bomb.play(vol),
+        }
+    }
+
     pub fn update_bgm(&mut self) {
         if let Some(id) = self.current_played_bgm {
             self.bgm[id].update_stream();
@@ -168,6 +190,14 @@ impl<'a> AudioAssets<'a> {
     }
 }
 
+// INFO : Every sprite sheet an entity or a pattern file can refer to by name
+pub const SPRITE_SHEETS: [(&str, &str); 4] = [
+    ("commons_sprite", "./assets/sprites/commons.png"),
+    ("reimu_sprite", "./assets/sprites/reimu/reimu.png"),
+    ("miko_sprite", "./assets/sprites/miko/miko.png"),
+    ("fairy_sprite", "./assets/sprites/fairy/fairy.png"),
+];
+
 impl Assets {
     pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread) -> Self {
         let textures = HashMap::new();
@@ -192,20 +222,9 @@ impl Assets {
         );
         assets.load_textures(rl, thread, "./assets/characters/miko/miko.png", "miko_char");
 
-        assets.load_textures(rl, thread, "./assets/sprites/commons.png", "commons_sprite");
-        assets.load_textures(
-            rl,
-            thread,
-            "./assets/sprites/reimu/reimu.png",
-            "reimu_sprite",
-        );
-        assets.load_textures(rl, thread, "./assets/sprites/miko/miko.png", "miko_sprite");
-        assets.load_textures(
-            rl,
-            thread,
-            "./assets/sprites/fairy/fairy.png",
-            "fairy_sprite",
-        );
+        for (name, path) in SPRITE_SHEETS {
+            assets.load_textures(rl, thread, path, name);
+        }
 
         assets
     }
@@ -2,6 +2,18 @@ use raylib::audio::{RaylibAudio, Sound};
 
 use crate::state::State;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SoundEffect {
+    Select,
+    Death,
+    GenericShoot,
+    Shot1,
+    SpellEnd,
+    Graze,
+    EnemyDeath,
+    Bomb,
+}
+
 pub struct Sfx<'a>(Sound<'a>);
 
 impl<'a> Sfx<'a> {
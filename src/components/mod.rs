@@ -1,24 +1,38 @@
-use std::collections::VecDeque;
+use std::{
+    collections::VecDeque,
+    f32::consts::{FRAC_PI_2, PI, TAU},
+};
 
-use hecs::{Entity, World};
+use hecs::Entity;
 use num_complex::Complex;
 use raylib::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{
+    assets::sfx::SoundEffect,
     cmpx,
-    math::ToVec2,
-    state::State,
-    systems::update_cooldown_attack,
-    utility::{get_sprite_coord, timer::Timer},
-    vec2,
+    entity::{create_bomb, create_player_bullet},
+    math::{ComplexExt, ToVec2},
+    simulation::Simulation,
+    systems::{magnetize_items, update_cooldown_attack},
+    utility::{clock::TICK_DELTA, get_sprite_coord, timer::Timer},
 };
 
 pub struct Player;
 pub struct Controllable;
 pub struct Enemy;
 pub struct Boss;
+// INFO : Character art shown in the spell card cutin
+pub struct Portrait(pub String);
+// INFO : Boss waiting before its next move, the attack and its timer are frozen and the
+// boss can't be hurt until the remaining time (in second) run out
+pub struct PhaseIntro(pub f32);
 pub struct Bullet;
 pub struct DieOffScreen;
+// INFO : An enemy bullet that already gave graze, a bullet can only be grazed once
+pub struct Grazed;
+// INFO : Purely visual entity, despawned when the remaining lifetime run out
+pub struct Particle(pub f32);
 pub struct Damage(pub f32);
 
 #[derive(Debug, Clone)]
@@ -29,6 +43,11 @@ pub struct Cooldown(pub Timer);
 pub struct Focusable(pub f32, pub f32);
 pub struct RotatingBgBoss(pub f32, pub f32);
 pub struct InvulnerableDelay(pub f32);
+// INFO : The player got hit and has the remaining time (in second) to deathbomb
+pub struct Dying(pub f32);
+// INFO : Position on the previous tick, used to interpolate rendering between ticks
+#[derive(Debug, Clone, Copy)]
+pub struct PreviousPosition(pub Complex<f32>);
 
 impl Cooldown {
     pub fn new(time: f32) -> Self {
@@ -58,8 +77,152 @@ impl Wanderable {
     }
 }
 
+// INFO : Attack of a regular enemy, it start firing once the delay (in second) run out
+#[derive(Debug, Clone)]
+pub struct EnemyAttack {
+    pub delay: f32,
+    pub attack: AttackMove,
+}
+
+impl EnemyAttack {
+    pub fn new(delay: f32, attack: AttackMove) -> Self {
+        Self { delay, attack }
+    }
+}
+
+// INFO : Replace the MoveParams of the entity at the given time (in second) since spawn
+#[derive(Debug, Clone)]
+pub struct MovementPath {
+    pub elapsed: f32,
+    pub steps: VecDeque<(f32, MoveParams)>,
+}
+
+impl MovementPath {
+    pub fn new(mut steps: Vec<(f32, MoveParams)>) -> Self {
+        steps.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self {
+            elapsed: 0.,
+            steps: steps.into(),
+        }
+    }
+
+    // INFO : Only the latest step due matter when several are due in the same tick
+    pub fn advance(&mut self, delta: f32) -> Option<MoveParams> {
+        self.elapsed += delta;
+        let mut due = None;
+        while self
+            .steps
+            .front()
+            .is_some_and(|(at, _)| *at <= self.elapsed)
+        {
+            due = self.steps.pop_front().map(|(_, movement)| movement);
+        }
+        due
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Power,
+    Point,
+    BombPiece,
+    LifePiece,
+}
+
+impl ItemKind {
+    pub fn sprite(&self) -> Sprite {
+        match self {
+            ItemKind::Power => Sprite::new("commons_sprite", 6, 0, 32., 32.),
+            ItemKind::Point => Sprite::new("commons_sprite", 5, 0, 32., 32.),
+            ItemKind::BombPiece => Sprite::new("commons_sprite", 4, 0, 32., 32.),
+            ItemKind::LifePiece => Sprite::new("commons_sprite", 7, 0, 32., 32.),
+        }
+    }
+}
+
+pub struct Item(pub ItemKind);
+// INFO : Item flying to the player, it always give its full value
+pub struct Magnetized;
+// INFO : What an enemy leave behind when killed
+#[derive(Debug, Clone, Default)]
+pub struct Drops(pub Vec<ItemKind>);
+
+#[derive(Debug, Clone)]
+pub struct BulletSetup(pub Sprite, pub CircleHitbox, pub Vec<BulletTask>);
+
+// INFO : Something a bullet do on its own at a given time (in second) after being fired
+#[derive(Debug, Clone)]
+pub struct BulletTask {
+    pub at: f32,
+    pub action: BulletAction,
+}
+
+#[derive(Debug, Clone)]
+pub enum BulletAction {
+    // INFO : angle is relative to the current direction unless absolute is set
+    SetVelocity {
+        speed: f32,
+        angle: f32,
+        absolute: bool,
+        motion: BulletMotion,
+    },
+    Aim {
+        speed: f32,
+        motion: BulletMotion,
+    },
+    // INFO : Replace the bullet with num children fanned over spread radian, children use the
+    // parent sprite and hitbox when no setup is given
+    Split {
+        num: u16,
+        spread: f32,
+        speed: f32,
+        aimed: bool,
+        motion: BulletMotion,
+        setup: Option<Box<BulletSetup>>,
+    },
+    SetSprite(Sprite),
+    SetHitbox(CircleHitbox),
+    Despawn,
+}
+
 #[derive(Debug, Clone)]
-pub struct BulletSetup(pub Sprite, pub CircleHitbox);
+pub struct BulletTimeline {
+    pub elapsed: f32,
+    pub tasks: VecDeque<BulletTask>,
+}
+
+impl BulletTimeline {
+    pub fn new(mut tasks: Vec<BulletTask>) -> Self {
+        tasks.sort_by(|a, b| a.at.total_cmp(&b.at));
+        Self {
+            elapsed: 0.,
+            tasks: tasks.into(),
+        }
+    }
+
+    // INFO : Every task due at the current time, in order
+    pub fn advance(&mut self, delta: f32) -> Vec<BulletAction> {
+        self.elapsed += delta;
+        let mut due = Vec::new();
+        while self
+            .tasks
+            .front()
+            .is_some_and(|task| task.at <= self.elapsed)
+        {
+            due.push(self.tasks.pop_front().unwrap().action);
+        }
+        due
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.tasks.is_empty()
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum AttackMove {
@@ -79,16 +242,135 @@ pub enum AttackMove {
         cooldown: Cooldown,
         setup: BulletSetup,
     },
+    // INFO : Evenly spaced arms, the whole pattern turn by angular_speed every fire
+    Spiral {
+        arms: u16,
+        speed: f32,
+        angular_speed: f32,
+        rotation: f32,
+        cooldown: Cooldown,
+        motion: BulletMotion,
+        setup: BulletSetup,
+    },
+    // INFO : Partial fan covering width radian, centered on the player when aimed
+    Arc {
+        num: u16,
+        speed: f32,
+        width: f32,
+        angle: f32,
+        aimed: bool,
+        cooldown: Cooldown,
+        motion: BulletMotion,
+        setup: BulletSetup,
+    },
+    // INFO : Random direction inside a cone with a random speed
+    Spray {
+        num: u16,
+        min_speed: f32,
+        max_speed: f32,
+        width: f32,
+        angle: f32,
+        aimed: bool,
+        cooldown: Cooldown,
+        motion: BulletMotion,
+        setup: BulletSetup,
+    },
+    // INFO : Full ring with one bullet always pointing at the player
+    AimedRing {
+        sides: u16,
+        speed: f32,
+        cooldown: Cooldown,
+        motion: BulletMotion,
+        setup: BulletSetup,
+    },
+    // INFO : Aimed fan repeated at several speed so it read as lines
+    Stacked {
+        num: u16,
+        layers: u16,
+        min_speed: f32,
+        max_speed: f32,
+        spread: f32,
+        cooldown: Cooldown,
+        motion: BulletMotion,
+        setup: BulletSetup,
+    },
+    // INFO : Ring that slow down to a stop then every bullet fly toward the player after delay
+    BurstReaim {
+        sides: u16,
+        speed: f32,
+        retention: f32,
+        delay: f32,
+        reaim_speed: f32,
+        cooldown: Cooldown,
+        setup: BulletSetup,
+    },
+    // INFO : Fan of num lasers spread radian apart
+    Laser {
+        num: u16,
+        angle: f32,
+        aimed: bool,
+        spread: f32,
+        cooldown: Cooldown,
+        laser: LaserSetup,
+    },
     Multiple(Vec<AttackMove>),
 }
 
+// INFO : How a freshly fired bullet turn its velocity into MoveParams
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BulletMotion {
+    #[default]
+    Linear,
+    // INFO : Acceleration relative to the direction of travel, side make the bullet curve
+    Accelerated {
+        along: f32,
+        side: f32,
+    },
+    // INFO : Start at (1 + boost) times the speed and settle down to the speed
+    Asymptotic {
+        boost: f32,
+        retention: f32,
+    },
+    // INFO : Pulled toward where the player was when fired
+    Towards {
+        attraction: f32,
+    },
+}
+
+impl BulletMotion {
+    pub fn to_move_params(&self, velocity: Complex<f32>, target: Complex<f32>) -> MoveParams {
+        match self {
+            BulletMotion::Linear => MoveParams::move_linear(velocity),
+            BulletMotion::Accelerated { along, side } => {
+                MoveParams::move_accelerated(velocity, velocity.normalize() * cmpx!(*along, *side))
+            }
+            BulletMotion::Asymptotic { boost, retention } => {
+                // INFO : move_asymptotic work per tick, acceleration need to be per second
+                let mut params = MoveParams::move_asymptotic(
+                    velocity * (1. + boost),
+                    velocity,
+                    cmpx!(*retention, 0.),
+                );
+                params.acceleration /= TICK_DELTA;
+                params
+            }
+            BulletMotion::Towards { attraction } => {
+                MoveParams::move_towards(velocity, target, cmpx!(*attraction, 0.))
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum BossMove {
+    // INFO : A survival spell card can't be shot down, it is captured by lasting until the timeout
     Spells {
         name: String,
         timeout: Timer,
         hp: Hitpoint,
         attack: AttackMove,
+        survival: bool,
     },
     NonSpells {
         timeout: Timer,
@@ -105,6 +387,7 @@ impl BossMove {
                 timeout,
                 hp,
                 attack,
+                ..
             } => {
                 timeout.update(d);
                 update_cooldown_attack(attack, d);
@@ -127,6 +410,7 @@ impl BossMove {
                 timeout,
                 hp,
                 attack,
+                ..
             } => timeout.elapsed() as i32,
             BossMove::NonSpells {
                 timeout,
@@ -143,6 +427,7 @@ impl BossMove {
                 timeout,
                 hp,
                 attack,
+                ..
             } => timeout.completed(),
             BossMove::NonSpells {
                 timeout,
@@ -159,6 +444,7 @@ impl BossMove {
                 timeout,
                 hp,
                 attack,
+                ..
             } => hp.damage(val),
             BossMove::NonSpells {
                 timeout,
@@ -175,6 +461,7 @@ impl BossMove {
                 timeout,
                 hp,
                 attack,
+                ..
             } => hp.hp,
             BossMove::NonSpells {
                 timeout,
@@ -191,6 +478,7 @@ impl BossMove {
                 timeout,
                 hp,
                 attack,
+                ..
             } => hp.max_hp,
             BossMove::NonSpells {
                 timeout,
@@ -207,6 +495,7 @@ impl BossMove {
                 timeout,
                 hp,
                 attack,
+                ..
             } => hp.is_dead(),
             BossMove::NonSpells {
                 timeout,
@@ -223,6 +512,7 @@ impl BossMove {
                 timeout,
                 hp,
                 attack,
+                ..
             } => *hp,
             BossMove::NonSpells {
                 timeout,
@@ -231,56 +521,315 @@ impl BossMove {
             } => *hp,
         }
     }
+
+    // INFO : Fraction of the timeout left, 1 when the move just started
+    pub fn progress(&self) -> f32 {
+        match self {
+            BossMove::Spells { timeout, .. } => timeout.progress(),
+            BossMove::NonSpells { timeout, .. } => timeout.progress(),
+        }
+    }
+
+    pub fn set_invulnerable(&mut self, invulnerable: bool) {
+        match self {
+            BossMove::Spells { hp, survival, .. } => hp.invulnerable = invulnerable || *survival,
+            BossMove::NonSpells { hp, .. } => hp.invulnerable = invulnerable,
+        }
+    }
+
+    pub fn is_survival(&self) -> bool {
+        matches!(self, BossMove::Spells { survival: true, .. })
+    }
+
+    pub fn spell_name(&self) -> Option<&str> {
+        match self {
+            BossMove::Spells { name, .. } => Some(name),
+            BossMove::NonSpells { .. } => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct BossMoves(pub VecDeque<BossMove>);
 
+impl BossMoves {
+    // INFO : A health bar is a nonspell followed by its spell card, or a lone move
+    fn bar_len(&self, start: usize) -> usize {
+        match (self.0.get(start), self.0.get(start + 1)) {
+            (Some(BossMove::NonSpells { .. }), Some(BossMove::Spells { .. })) => 2,
+            (Some(_), _) => 1,
+            (None, _) => 0,
+        }
+    }
+
+    pub fn current_bar(&self) -> impl Iterator<Item = &BossMove> {
+        self.0.iter().take(self.bar_len(0))
+    }
+
+    // INFO : Bars left after the current one, shown as stars
+    pub fn remaining_bars(&self) -> usize {
+        let mut start = self.bar_len(0);
+        let mut bars = 0;
+        loop {
+            let len = self.bar_len(start);
+            if len == 0 {
+                return bars;
+            }
+            start += len;
+            bars += 1;
+        }
+    }
+}
+
+// INFO : Last phase is over, the boss blow up once the remaining time (in second) run out
+pub struct BossDefeat(pub f32);
+
+// INFO : Orb following the player, index is its place in the layout of the current power tier
+pub struct PlayerOption(pub usize);
+
+// INFO : Option fire every few tick, the main shot fire every tick
+pub const OPTION_FIRE_INTERVAL: u64 = 4;
+
+// INFO : Player bullet steering toward the nearest enemy, turn rate is in radian per second
+#[derive(Debug, Clone, Copy)]
+pub struct Homing {
+    pub speed: f32,
+    pub turn_rate: f32,
+    pub target: Option<Entity>,
+}
+
+impl Homing {
+    pub fn new(speed: f32, turn_rate: f32) -> Self {
+        Self {
+            speed,
+            turn_rate,
+            target: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BasicPlayerAttack {
     ReimuA,
+    ReimuB,
 }
 
 impl BasicPlayerAttack {
-    pub fn spawn(&self, pos: Complex<f32>) -> impl FnOnce(&mut World, &mut State) {
-        move |world, state| {
-            state.audio.generic_shoot.play(state.sfx_volume);
-            world.spawn((
-                Player,
-                Bullet,
-                DieOffScreen,
-                Transform2D {
-                    position: pos,
-                    scale: vec2!(1.),
-                    rotation: 0.,
-                },
-                Damage(10.),
-                Sprite::new("reimu_sprite", 0, 4, 32., 32.),
-                MoveParams::move_linear(cmpx!(0., -2000.)),
-                CircleHitbox::new(2., vec2!(0.)),
-            ));
+    // INFO : Power 1.0 - 4.0, every full power point give one more option
+    pub fn options(&self, power: f32) -> usize {
+        power.clamp(1., 4.).floor() as usize
+    }
+
+    // INFO : Where the option sit relative to the player, spread wide when unfocused and
+    // packed in front of the player when focused
+    pub fn option_offset(&self, index: usize, count: usize, focused: bool) -> Complex<f32> {
+        match self {
+            BasicPlayerAttack::ReimuA => {
+                let unfocused: &[(f32, f32)] = match count {
+                    1 => &[(0., -32.)],
+                    2 => &[(-24., -8.), (24., -8.)],
+                    3 => &[(-28., -4.), (0., -32.), (28., -4.)],
+                    _ => &[(-36., 4.), (-16., -24.), (16., -24.), (36., 4.)],
+                };
+                let (x, y) = unfocused[index.min(unfocused.len() - 1)];
+                if focused {
+                    cmpx!(x * 0.35, -24. - y.abs() * 0.25)
+                } else {
+                    cmpx!(x, y)
+                }
+            }
+            // INFO : Half circle in front of the player, closing in when focused
+            BasicPlayerAttack::ReimuB => {
+                let radius = if focused { 20. } else { 32. };
+                let t = (index as f32 + 1.) / (count as f32 + 1.);
+                -Complex::cdir(PI * t) * radius
+            }
+        }
+    }
+
+    pub fn spawn(&self, pos: Complex<f32>, focused: bool) -> impl FnOnce(&mut Simulation) {
+        let kind = *self;
+        move |sim| {
+            sim.play_sfx(SoundEffect::GenericShoot);
+            match kind {
+                BasicPlayerAttack::ReimuA => {
+                    let needle = Sprite::new("reimu_sprite", 0, 4, 32., 32.);
+                    for offset in [-6., 6.] {
+                        create_player_bullet(
+                            &mut sim.world,
+                            pos + cmpx!(offset, 0.),
+                            cmpx!(0., -2000.),
+                            5.,
+                            needle,
+                        );
+                    }
+
+                    if sim.ticks % OPTION_FIRE_INTERVAL != 0 {
+                        return;
+                    }
+
+                    let options = sim
+                        .world
+                        .query::<(&PlayerOption, &Transform2D)>()
+                        .iter()
+                        .map(|(_, (_, transform))| transform.position)
+                        .collect::<Vec<_>>();
+                    for option in options {
+                        // INFO : Unfocused needle fan out, focused one all go straight
+                        let tilt = if focused {
+                            0.
+                        } else {
+                            (option.re - pos.re).clamp(-36., 36.) / 36. * 0.12
+                        };
+                        create_player_bullet(
+                            &mut sim.world,
+                            option,
+                            Complex::cdir(-FRAC_PI_2 + tilt) * 1800.,
+                            8.,
+                            needle,
+                        );
+                    }
+                }
+                BasicPlayerAttack::ReimuB => {
+                    let amulet = Sprite::new("reimu_sprite", 0, 5, 32., 32.);
+                    for offset in [-6., 6.] {
+                        create_player_bullet(
+                            &mut sim.world,
+                            pos + cmpx!(offset, 0.),
+                            cmpx!(0., -1400.),
+                            4.,
+                            amulet,
+                        );
+                    }
+
+                    if sim.ticks % OPTION_FIRE_INTERVAL != 0 {
+                        return;
+                    }
+
+                    let options = sim
+                        .world
+                        .query::<(&PlayerOption, &Transform2D)>()
+                        .iter()
+                        .map(|(_, (_, transform))| transform.position)
+                        .collect::<Vec<_>>();
+                    // INFO : Focused amulet are launched straight up and steer less
+                    let (launch, homing) = if focused {
+                        (0., Homing::new(900., 4.))
+                    } else {
+                        (0.5, Homing::new(700., 8.))
+                    };
+                    for option in options {
+                        let side = (option.re - pos.re).signum();
+                        let bullet = create_player_bullet(
+                            &mut sim.world,
+                            option,
+                            Complex::cdir(-FRAC_PI_2 + side * launch) * homing.speed,
+                            7.,
+                            amulet,
+                        );
+                        let _ = sim.world.insert_one(bullet, homing);
+                    }
+                }
+            }
         }
     }
 }
 
+// INFO : Part of a player bomb, every tick it clear the enemy bullet in radius and deal
+// damage per second to the enemy in it. With a blast it explode when touching an enemy or
+// when the lifetime run out, dealing blast damage to everything in the blast radius.
+// A bomb that follow stay centered on the player
+#[derive(Debug, Clone, Copy)]
+pub struct Bomb {
+    pub lifetime: f32,
+    pub radius: f32,
+    pub damage: f32,
+    pub blast: Option<(f32, f32)>,
+    pub follow: bool,
+}
+
+// INFO : Invulnerability given on top of the bomb duration
+pub const BOMB_INVULNERABLE_MARGIN: f32 = 1.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlayerSpells {
-    ReimuA,
+    ReimuA, // Fantasy Seal
+    ReimuB, // Evil Sealing Circle
 }
 
 impl PlayerSpells {
-    pub fn spawn(&self, pos: Complex<f32>) -> impl FnOnce(&mut World, &mut State) {
-        move |world, state| {
-            if state.score.spell > 0 {
-                state.score.spell -= 1;
+    pub fn duration(&self) -> f32 {
+        match self {
+            PlayerSpells::ReimuA => 3.,
+            PlayerSpells::ReimuB => 1.5,
+        }
+    }
+
+    pub fn spawn(&self, pos: Complex<f32>) -> impl FnOnce(&mut Simulation) {
+        let kind = *self;
+        move |sim| {
+            let active = sim.world.query::<&Bomb>().iter().next().is_some();
+            if sim.score.spell <= 0 || active {
+                return;
+            }
+            sim.score.spell -= 1;
+            sim.score.rank.bomb();
+            sim.fail_spell_card();
+            sim.play_sfx(SoundEffect::Bomb);
+
+            match kind {
+                PlayerSpells::ReimuA => {
+                    // INFO : Orbs spread around the player before homing on the enemies,
+                    // the later one live a bit longer so they don't all explode at once
+                    for i in 0..6 {
+                        let angle = i as f32 / 6. * TAU - FRAC_PI_2;
+                        let bomb = Bomb {
+                            lifetime: kind.duration() - (5 - i) as f32 * 0.25,
+                            radius: 24.,
+                            damage: 60.,
+                            blast: Some((96., 500.)),
+                            follow: false,
+                        };
+                        create_bomb(
+                            &mut sim.world,
+                            pos,
+                            Complex::cdir(angle) * 240.,
+                            bomb,
+                            Sprite::new("reimu_sprite", 0, 3, 32., 32.),
+                            2.,
+                        );
+                    }
+                }
+                PlayerSpells::ReimuB => {
+                    let bomb = Bomb {
+                        lifetime: kind.duration(),
+                        radius: 96.,
+                        damage: 400.,
+                        blast: None,
+                        follow: true,
+                    };
+                    create_bomb(
+                        &mut sim.world,
+                        pos,
+                        cmpx!(0.),
+                        bomb,
+                        Sprite::new("reimu_sprite", 0, 6, 64., 64.),
+                        3.,
+                    );
+                }
             }
-            let pendings = world
-                .query::<(&Enemy, &Bullet)>()
-                .iter()
-                .map(|(id, _)| id.clone())
-                .collect::<Vec<Entity>>();
 
-            for i in pendings {
-                let _ = world.despawn(i);
+            let player = sim
+                .world
+                .query::<(&Player, &Controllable)>()
+                .iter()
+                .map(|(id, _)| id)
+                .next();
+            if let Some(player) = player {
+                let time = kind.duration() + BOMB_INVULNERABLE_MARGIN;
+                let _ = sim.world.insert_one(player, InvulnerableDelay(time));
             }
+            magnetize_items(&mut sim.world);
         }
     }
 }
@@ -304,7 +853,7 @@ impl PlayerAttack {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy)]
 pub struct Sprite {
     pub name: &'static str,
     pub src: Rectangle,
@@ -533,6 +1082,118 @@ impl CircleHitbox {
             target_hitbox.radius,
         )
     }
+
+    // INFO : Capsule test, the segment a-b swept by radius
+    pub fn is_intersect_segment(
+        &self,
+        current_pos: &Transform2D,
+        a: Complex<f32>,
+        b: Complex<f32>,
+        radius: f32,
+    ) -> bool {
+        let center = current_pos.position + cmpx!(self.offset.x, self.offset.y);
+        let reach = self.radius + radius;
+        center.segment_distance_squared(&a, &b) <= reach * reach
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LaserShape {
+    // INFO : Fixed origin, angular_speed in radian per second
+    Straight {
+        length: f32,
+        #[serde(default)]
+        angular_speed: f32,
+    },
+    // INFO : The head move like a bullet and the body follow its trail, length in tick
+    Curvy {
+        speed: f32,
+        length: u16,
+        #[serde(default)]
+        motion: BulletMotion,
+    },
+}
+
+// INFO : Every duration in second, the laser is harmless during warning, then grow from 0 to
+// width during grow and stay for duration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LaserSetup {
+    pub shape: LaserShape,
+    pub width: f32,
+    #[serde(default)]
+    pub warning: f32,
+    #[serde(default)]
+    pub grow: f32,
+    pub duration: f32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaserPhase {
+    Warning,
+    Growing(f32),
+    Active,
+    Done,
+}
+
+#[derive(Debug, Clone)]
+pub struct Laser {
+    pub setup: LaserSetup,
+    pub angle: f32,
+    pub elapsed: f32,
+    // INFO : Trail of a curvy laser, the head first
+    pub points: VecDeque<Complex<f32>>,
+}
+
+impl Laser {
+    pub fn new(setup: LaserSetup, angle: f32) -> Self {
+        Self {
+            setup,
+            angle,
+            elapsed: 0.,
+            points: VecDeque::new(),
+        }
+    }
+
+    pub fn phase(&self) -> LaserPhase {
+        let LaserSetup {
+            warning,
+            grow,
+            duration,
+            ..
+        } = self.setup;
+        if self.elapsed < warning {
+            LaserPhase::Warning
+        } else if self.elapsed < warning + grow {
+            LaserPhase::Growing((self.elapsed - warning) / grow)
+        } else if self.elapsed < warning + grow + duration {
+            LaserPhase::Active
+        } else {
+            LaserPhase::Done
+        }
+    }
+
+    pub fn width(&self) -> f32 {
+        match self.phase() {
+            LaserPhase::Warning | LaserPhase::Done => 0.,
+            LaserPhase::Growing(t) => self.setup.width * t,
+            LaserPhase::Active => self.setup.width,
+        }
+    }
+
+    pub fn segments(&self, origin: Complex<f32>) -> Vec<(Complex<f32>, Complex<f32>)> {
+        match self.setup.shape {
+            LaserShape::Straight { length, .. } => {
+                vec![(origin, origin + Complex::cdir(self.angle) * length)]
+            }
+            LaserShape::Curvy { .. } => self
+                .points
+                .iter()
+                .zip(self.points.iter().skip(1))
+                .map(|(a, b)| (*a, *b))
+                .collect(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use raylib::prelude::*;
 
-#[derive(Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq)]
 pub enum Action {
     Attack,
     Spell,
@@ -15,6 +15,49 @@ pub enum Action {
 
     Escape,
     Accept,
+
+    Debug,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::Attack,
+        Action::Spell,
+        Action::Focus,
+        Action::Up,
+        Action::Right,
+        Action::Left,
+        Action::Down,
+        Action::Escape,
+        Action::Accept,
+        Action::Debug,
+    ];
+
+    fn bit(&self) -> u16 {
+        1 << *self as u16
+    }
+}
+
+// INFO : Snapshot of every action for a single frame, this is what the gameplay reads
+// so it can be recorded and replayed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActionState {
+    pub down: u16,
+    pub pressed: u16,
+}
+
+impl ActionState {
+    pub fn new(down: u16, pressed: u16) -> Self {
+        Self { down, pressed }
+    }
+
+    pub fn is_down(&self, action: Action) -> bool {
+        self.down & action.bit() != 0
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.pressed & action.bit() != 0
+    }
 }
 
 pub enum Combination {
@@ -40,6 +83,7 @@ pub fn init_controls() -> Controls {
 
     controls.add(Action::Escape, Combination::Single(KeyboardKey::KEY_ESCAPE));
     controls.add(Action::Accept, Combination::Single(KeyboardKey::KEY_ENTER));
+    controls.add(Action::Debug, Combination::Single(KeyboardKey::KEY_F3));
 
     controls
 }
@@ -60,6 +104,20 @@ impl Controls {
         })
     }
 
+    pub fn snapshot(&self, d: &RaylibDrawHandle) -> ActionState {
+        let mut input = ActionState::default();
+        for action in Action::ALL {
+            if self.is_down(action, d) {
+                input.down |= action.bit();
+            }
+            if self.is_pressed(action, d) {
+                input.pressed |= action.bit();
+            }
+        }
+
+        input
+    }
+
     pub fn is_down(&self, action: Action, d: &RaylibDrawHandle) -> bool {
         self.0.get(&action).map_or(false, |a| match a {
             Combination::Single(key) => d.is_key_down(*key),
@@ -1,9 +1,14 @@
-#[derive(Debug)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Difficulty {
     Easy,    // Plebs
     Normal,  // Good
     Hard,    // Master
     Lunatic, // Master of Master
+    // INFO : Unlocked by clearing the game, it has its own stage and play like Lunatic
+    Extra,
 }
 
 impl Difficulty {
@@ -13,7 +18,34 @@ impl Difficulty {
             Difficulty::Easy => easy,
             Difficulty::Normal => normal,
             Difficulty::Hard => hard,
-            Difficulty::Lunatic => lunatic,
+            Difficulty::Lunatic | Difficulty::Extra => lunatic,
+        }
+    }
+
+    pub fn score_multiplier(&self) -> f32 {
+        self.difficulty_value(0.5, 1.0, 1.2, 1.5)
+    }
+
+    // INFO : Pattern speed and density factor at the lowest and highest rank
+    pub fn rank_range(&self) -> (f32, f32) {
+        self.difficulty_value((0.9, 1.0), (0.9, 1.1), (0.95, 1.2), (1.0, 1.3))
+    }
+
+    pub fn id(&self) -> u8 {
+        match self {
+            Difficulty::Extra => 4,
+            _ => self.difficulty_value(0, 1, 2, 3),
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Difficulty::Easy),
+            1 => Some(Difficulty::Normal),
+            2 => Some(Difficulty::Hard),
+            3 => Some(Difficulty::Lunatic),
+            4 => Some(Difficulty::Extra),
+            _ => None,
         }
     }
 }
@@ -25,6 +57,7 @@ impl AsRef<str> for Difficulty {
             Difficulty::Normal => "Normal",
             Difficulty::Hard => "Hard",
             Difficulty::Lunatic => "Lunatic",
+            Difficulty::Extra => "Extra",
         }
     }
 }
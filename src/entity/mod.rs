@@ -1,16 +1,21 @@
-use std::collections::VecDeque;
-
-use hecs::World;
+use hecs::{Entity, World};
+use num_complex::Complex;
 use raylib::prelude::*;
 
 use crate::{
     cmpx,
     components::{
-        Attack, AttackMove, BasicPlayerAttack, Boss, BossMove, BossMoves, Bullet, BulletSetup,
-        CircleHitbox, Controllable, Cooldown, DieOffScreen, Enemy, Focusable, Hitpoint, MoveParams,
-        Player, PlayerAttack, PlayerSpells, RotatingBgBoss, Sprite, Transform2D, Wanderable,
+        Attack, BasicPlayerAttack, Bomb, Boss, Bullet, CircleHitbox, Controllable, Cooldown,
+        Damage, DieOffScreen, Drops, Enemy, Focusable, Hitpoint, Homing, Item, ItemKind, Laser,
+        LaserSetup, LaserShape, MoveParams, Particle, PhaseIntro, Player, PlayerAttack,
+        PlayerOption, PlayerSpells, Portrait, PreviousPosition, RotatingBgBoss, Sprite,
+        Transform2D, Wanderable,
     },
-    utility::timer::Timer,
+    difficulty::Difficulty,
+    math::ComplexExt,
+    pattern::BossPattern,
+    player::PlayerKind,
+    utility::clock::TICK_DELTA,
     vec2,
 };
 pub fn create_enemy_bullet(
@@ -19,165 +24,214 @@ pub fn create_enemy_bullet(
     sprite: Sprite,
     movement: MoveParams,
     hitbox: CircleHitbox,
-) {
+) -> Entity {
     world.spawn((
         Enemy,
         Bullet,
         DieOffScreen,
         movement,
+        PreviousPosition(transform.position),
         transform,
         sprite,
         hitbox,
+    ))
+}
+
+pub fn create_player_bullet(
+    world: &mut World,
+    position: Complex<f32>,
+    velocity: Complex<f32>,
+    damage: f32,
+    sprite: Sprite,
+) -> Entity {
+    world.spawn((
+        Player,
+        Bullet,
+        DieOffScreen,
+        Transform2D::new(position, vec2!(1.), velocity.rot()),
+        PreviousPosition(position),
+        Damage(damage),
+        sprite,
+        MoveParams::move_linear(velocity),
+        CircleHitbox::new(2., vec2!(0.)),
+    ))
+}
+
+// INFO : Only the Fantasy Seal orbs move, and they home on the enemies once launched
+pub fn create_bomb(
+    world: &mut World,
+    position: Complex<f32>,
+    velocity: Complex<f32>,
+    bomb: Bomb,
+    sprite: Sprite,
+    scale: f32,
+) -> Entity {
+    let id = world.spawn((
+        bomb,
+        sprite,
+        Transform2D::new(position, vec2!(scale), 0.),
+        PreviousPosition(position),
+        MoveParams::move_linear(velocity),
     ));
+    if velocity.norm_sqr() > 0. {
+        let _ = world.insert_one(id, Homing::new(velocity.norm(), 4.));
+    }
+    id
+}
+
+pub fn create_player_option(world: &mut World, position: Complex<f32>, index: usize) -> Entity {
+    world.spawn((
+        PlayerOption(index),
+        Sprite::new("reimu_sprite", 0, 3, 32., 32.),
+        Transform2D::new(position, vec2!(1.), 0.),
+        PreviousPosition(position),
+    ))
+}
+
+pub fn spawn_player(world: &mut World, player: PlayerKind) {
+    match player {
+        PlayerKind::ReimuA => reimu_a(world),
+        PlayerKind::ReimuB => reimu_b(world),
+    }
+}
+
+pub fn create_laser(
+    world: &mut World,
+    position: Complex<f32>,
+    angle: f32,
+    setup: LaserSetup,
+    target: Complex<f32>,
+) -> Entity {
+    let transform = Transform2D::new(position, vec2!(1.), 0.);
+    match setup.shape {
+        LaserShape::Straight { .. } => world.spawn((
+            Enemy,
+            Bullet,
+            Laser::new(setup, angle),
+            PreviousPosition(position),
+            transform,
+        )),
+        LaserShape::Curvy { speed, motion, .. } => world.spawn((
+            Enemy,
+            Bullet,
+            Laser::new(setup, angle),
+            PreviousPosition(position),
+            transform,
+            motion.to_move_params(Complex::cdir(angle) * speed, target),
+        )),
+    }
 }
 
+pub fn create_particle(
+    world: &mut World,
+    position: Complex<f32>,
+    velocity: Complex<f32>,
+    sprite: Sprite,
+    lifetime: f32,
+) -> Entity {
+    world.spawn((
+        Particle(lifetime),
+        sprite,
+        Transform2D::new(position, vec2!(1.), 0.),
+        PreviousPosition(position),
+        MoveParams::move_dampen(velocity, 0.9),
+    ))
+}
+
+// INFO : Retention is applied once per tick, 0.85 per frame at 120 FPS is 0.85² per tick
+pub const PLAYER_RETENTION: f32 = 0.85 * 0.85;
+// INFO : Keep the 120 FPS top speed, 5000 / 120 / 0.15 = PLAYER_ACCELERATION / 60 / 0.2775
+pub const PLAYER_ACCELERATION: f32 = 4625.;
+
 pub fn reimu_a(world: &mut World) {
+    reimu(world, BasicPlayerAttack::ReimuA, PlayerSpells::ReimuA);
+}
+
+pub fn reimu_b(world: &mut World) {
+    reimu(world, BasicPlayerAttack::ReimuB, PlayerSpells::ReimuB);
+}
+
+fn reimu(world: &mut World, basic: BasicPlayerAttack, spells: PlayerSpells) {
     world.spawn((
         Player,
         Controllable,
         Sprite::new("reimu_sprite", 0, 0, 32., 32.),
         Transform2D::new(cmpx!(150., 400.), vec2!(1.), 0.),
-        MoveParams::move_dampen(cmpx!(0.), 0.85),
+        PreviousPosition(cmpx!(150., 400.)),
+        MoveParams::move_dampen(cmpx!(0.), PLAYER_RETENTION),
         Focusable(0., 300.),
         CircleHitbox::new(4.5, vec2!(0.)),
         PlayerAttack::new(
-            Attack::new(Cooldown::new(1.), BasicPlayerAttack::ReimuA),
-            Attack::new(Cooldown::new(1.), PlayerSpells::ReimuA),
+            Attack::new(Cooldown::new(1.), basic),
+            Attack::new(Cooldown::new(1.), spells),
         ),
     ));
 }
 
-pub fn miko(world: &mut World) {
+// INFO : Pop up a little then settle to a slow fall, move_asymptotic work per tick so the
+// acceleration need to be per second
+fn item_fall() -> MoveParams {
+    let mut params =
+        MoveParams::move_asymptotic(cmpx!(0., -150.), cmpx!(0., 120.), cmpx!(0.92, 0.));
+    params.acceleration /= TICK_DELTA;
+    params
+}
+
+pub fn create_item(world: &mut World, position: Complex<f32>, kind: ItemKind) -> Entity {
+    world.spawn((
+        Item(kind),
+        DieOffScreen,
+        kind.sprite(),
+        Transform2D::new(position, vec2!(1.), 0.),
+        PreviousPosition(position),
+        item_fall(),
+        CircleHitbox::new(8., vec2!(0.)),
+    ))
+}
+
+pub fn fairy(world: &mut World, position: Complex<f32>, movement: MoveParams) -> Entity {
+    world.spawn((
+        Enemy,
+        DieOffScreen,
+        Sprite::new("fairy_sprite", 0, 0, 32., 32.),
+        Transform2D::new(position, vec2!(1.), 0.),
+        PreviousPosition(position),
+        movement,
+        CircleHitbox::new(10., vec2!(0.)),
+        Hitpoint::new(100.),
+        Drops(vec![ItemKind::Power]),
+    ))
+}
+
+// INFO : Time the boss take to fly in, it can't attack nor be hurt meanwhile
+const BOSS_ENTRANCE: f32 = 1.5;
+
+pub fn miko(world: &mut World, pattern: &BossPattern, difficulty: Difficulty) {
+    let mut moves = pattern.scaled(difficulty).to_boss_moves();
+    if let Some(first) = moves.0.front_mut() {
+        first.set_invulnerable(true);
+    }
+    let mut wander = Wanderable::new(
+        Rectangle::new(10., 10., 364., 200.),
+        cmpx!(150., 50.),
+        400.,
+        4.,
+    );
+    wander.target_pos = Some(cmpx!(150., 50.));
+
     world.spawn((
         Enemy,
         Boss,
         RotatingBgBoss(0., 300.),
+        Portrait("miko_char".to_owned()),
         Sprite::new("miko_sprite", 0, 0, 32., 64.),
-        Transform2D::new(cmpx!(150., 50.), vec2!(1.), 0.),
-        Wanderable::new(
-            Rectangle::new(10., 10., 364., 200.),
-            cmpx!(150., 50.),
-            400.,
-            4.,
-        ),
+        Transform2D::new(cmpx!(150., -64.), vec2!(1.), 0.),
+        PreviousPosition(cmpx!(150., -64.)),
+        wander,
         MoveParams::move_linear(cmpx!(0.)),
         CircleHitbox::new(12., vec2!(0.)),
-        BossMoves(VecDeque::from([
-            BossMove::NonSpells {
-                timeout: Timer::new(120., false),
-                hp: Hitpoint::new(15000.),
-                attack: AttackMove::Multiple(Vec::from([
-                    AttackMove::Circle {
-                        sides: 32,
-                        speed: 140.,
-                        rotation_per_fire: 2.,
-                        rotation: 12.,
-                        cooldown: Cooldown(Timer::new(1.9, true)),
-                        setup: BulletSetup(
-                            Sprite::new("miko_sprite", 0, 3, 32., 32.),
-                            CircleHitbox::new(2.5, vec2!(0.)),
-                        ),
-                    },
-                    AttackMove::Circle {
-                        sides: 24,
-                        speed: 120.,
-                        rotation_per_fire: 2.,
-                        rotation: 8.,
-                        cooldown: Cooldown(Timer::new(1.0, true)),
-                        setup: BulletSetup(
-                            Sprite::new("miko_sprite", 0, 3, 32., 32.),
-                            CircleHitbox::new(2.5, vec2!(0.)),
-                        ),
-                    },
-                    AttackMove::Circle {
-                        sides: 24,
-                        speed: 150.,
-                        rotation_per_fire: 2.,
-                        rotation: 8.,
-                        cooldown: Cooldown(Timer::new(2.0, true)),
-                        setup: BulletSetup(
-                            Sprite::new("miko_sprite", 0, 3, 32., 32.),
-                            CircleHitbox::new(2.5, vec2!(0.)),
-                        ),
-                    },
-                    AttackMove::AtPlayer {
-                        num: 12,
-                        speed: 145.,
-                        spread: 12.,
-                        total_shoot: 12,
-                        cooldown: Cooldown(Timer::new(2.0, true)),
-                        setup: BulletSetup(
-                            Sprite::new("miko_sprite", 0, 4, 32., 32.),
-                            CircleHitbox::new(2.5, vec2!(0.)),
-                        ),
-                    },
-                ])),
-            },
-            BossMove::Spells {
-                name: "Hermit Sign 'Taoist of the Land of the Rising Sun'".to_owned(),
-                timeout: Timer::new(240., false),
-                hp: Hitpoint::new(30000.),
-                attack: AttackMove::Multiple(
-                    [
-                        AttackMove::Circle {
-                            sides: 32,
-                            speed: 240.,
-                            rotation_per_fire: 2.,
-                            rotation: 12.,
-                            cooldown: Cooldown(Timer::new(1.9, true)),
-                            setup: BulletSetup(
-                                Sprite::new("miko_sprite", 0, 3, 32., 32.),
-                                CircleHitbox::new(2.5, vec2!(0.)),
-                            ),
-                        },
-                        AttackMove::Circle {
-                            sides: 24,
-                            speed: 220.,
-                            rotation_per_fire: 2.,
-                            rotation: 8.,
-                            cooldown: Cooldown(Timer::new(1.0, true)),
-                            setup: BulletSetup(
-                                Sprite::new("miko_sprite", 0, 3, 32., 32.),
-                                CircleHitbox::new(2.5, vec2!(0.)),
-                            ),
-                        },
-                        AttackMove::Circle {
-                            sides: 24,
-                            speed: 250.,
-                            rotation_per_fire: 2.,
-                            rotation: 8.,
-                            cooldown: Cooldown(Timer::new(2.0, true)),
-                            setup: BulletSetup(
-                                Sprite::new("miko_sprite", 0, 3, 32., 32.),
-                                CircleHitbox::new(2.5, vec2!(0.)),
-                            ),
-                        },
-                        AttackMove::Circle {
-                            sides: 12,
-                            speed: 240.,
-                            rotation_per_fire: 2.,
-                            rotation: 3.,
-                            cooldown: Cooldown(Timer::new(2.5, true)),
-                            setup: BulletSetup(
-                                Sprite::new("miko_sprite", 0, 4, 32., 32.),
-                                CircleHitbox::new(2.5, vec2!(0.)),
-                            ),
-                        },
-                        AttackMove::AtPlayer {
-                            num: 24,
-                            speed: 250.,
-                            spread: 20.,
-                            total_shoot: 12,
-                            cooldown: Cooldown(Timer::new(5., true)),
-                            setup: BulletSetup(
-                                Sprite::new("reimu_sprite", 0, 6, 64., 64.),
-                                CircleHitbox::new(10., vec2!(0.)),
-                            ),
-                        },
-                    ]
-                    .to_vec(),
-                ),
-            },
-        ])),
+        PhaseIntro(BOSS_ENTRANCE),
+        moves,
+        Drops([[ItemKind::Power; 6], [ItemKind::Point; 6]].concat()),
     ));
 }
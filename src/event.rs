@@ -1,9 +1,9 @@
-use crate::{scenes::stage_view::StageView, state::State};
+use crate::simulation::Simulation;
 
 pub struct Event {
     pub start: f32,
     pub is_spawned: bool,
-    pub action: Option<Box<dyn FnOnce(&mut StageView, &mut State)>>,
+    pub action: Option<Box<dyn FnOnce(&mut Simulation)>>,
 }
 
 #[derive(Default)]
@@ -13,23 +13,51 @@ pub struct EventManager {
 }
 
 impl EventManager {
-    pub fn add(&mut self, start: f32, action: impl FnOnce(&mut StageView, &mut State) + 'static) {
+    pub fn add(&mut self, start: f32, action: impl FnOnce(&mut Simulation) + 'static) {
         self.lists.push(Event {
             start,
             is_spawned: false,
             action: Some(Box::new(action)),
         });
     }
-    pub fn update(&mut self, stage: &mut StageView, state: &mut State, time: f32) {
-        self.timer += time;
+    // INFO : The timer is held during a boss fight so whatever come after the boss in the
+    // stage only happen once it's defeated
+    pub fn update(&mut self, sim: &mut Simulation, time: f32) {
+        if !sim.boss_fight {
+            self.timer += time;
+        }
         self.lists
             .iter_mut()
             .filter(|event| !event.is_spawned && event.start < self.timer)
-            .for_each(|event| match event.action.take() {
-                Some(action) => (action)(stage, state),
-                None => {}
+            .for_each(|event| {
+                event.is_spawned = true;
+                if let Some(action) = event.action.take() {
+                    (action)(sim)
+                }
             });
 
         self.lists.retain(|event| !event.is_spawned);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score::ScoreData;
+
+    #[test]
+    fn fired_events_are_removed() {
+        let mut sim = Simulation::new(EventManager::default(), ScoreData::default(), 0);
+        let mut event = EventManager::default();
+        event.add(0.5, |sim| sim.score.score += 1);
+        event.add(2.0, |sim| sim.score.score += 10);
+
+        event.update(&mut sim, 1.0);
+        assert_eq!(sim.score.score, 1);
+        assert_eq!(event.lists.len(), 1);
+
+        event.update(&mut sim, 1.5);
+        assert_eq!(sim.score.score, 11);
+        assert!(event.lists.is_empty());
+    }
+}
@@ -10,9 +10,16 @@ pub mod difficulty;
 pub mod entity;
 pub mod event;
 pub mod math;
+pub mod pattern;
+pub mod player;
+pub mod rank;
 pub mod renderer;
+pub mod replay;
+pub mod save;
 pub mod scenes;
 pub mod score;
+pub mod simulation;
+pub mod spell_history;
 pub mod stage;
 pub mod state;
 pub mod systems;
@@ -19,6 +19,7 @@ pub trait ComplexExt: CartesianExt {
     fn normalize(&self) -> Self;
     fn rot(&self) -> f32;
     fn lerp(&self, other: &Self, t: f32) -> Self;
+    fn segment_distance_squared(&self, a: &Self, b: &Self) -> f32;
 }
 
 impl<T> CartesianExt for Complex<T> {
@@ -39,7 +40,7 @@ impl ComplexExt for Complex<f32> {
     }
 
     fn lerp(&self, other: &Self, t: f32) -> Self {
-        t * (self - other) + self
+        t * (other - self) + self
     }
 
     fn cdir(angle: f32) -> Self {
@@ -62,6 +63,17 @@ impl ComplexExt for Complex<f32> {
         self.arg().to_degrees() + 90.
     }
 
+    // INFO : Squared distance from self to the closest point of the segment a-b
+    fn segment_distance_squared(&self, a: &Self, b: &Self) -> f32 {
+        let ab = b - a;
+        let len = ab.norm_sqr();
+        if len == 0. {
+            return self.distance_squared(a);
+        }
+        let t = ((self - a) * ab.conj()).re / len;
+        self.distance_squared(&a.lerp(b, t.clamp(0., 1.)))
+    }
+
     // fn rot(&self, other: &Self) -> f32 {
     //     (self.arg() - other.arg()).to_degrees()
     // }
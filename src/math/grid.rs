@@ -0,0 +1,144 @@
+use num_complex::Complex;
+
+use super::ComplexExt;
+
+// INFO : Uniform grid over the play field used as broad phase for circle collision.
+// Every item live in the single cell containing its center, a query look at every cell
+// that could hold a circle touching it (query radius + biggest inserted radius) and only
+// return the item that actually overlap, so nothing is ever returned twice.
+// Item outside the field are clamped into the border cells.
+#[derive(Debug, Clone)]
+pub struct SpatialGrid<T> {
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    max_radius: f32,
+    cells: Vec<Vec<(Complex<f32>, f32, T)>>,
+}
+
+impl<T: Copy> SpatialGrid<T> {
+    pub fn new(width: f32, height: f32, cell_size: f32) -> Self {
+        let columns = (width / cell_size).ceil().max(1.) as usize;
+        let rows = (height / cell_size).ceil().max(1.) as usize;
+        Self {
+            cell_size,
+            columns,
+            rows,
+            max_radius: 0.,
+            cells: vec![Vec::new(); columns * rows],
+        }
+    }
+
+    // INFO : Keep the allocation of every cell so rebuilding each tick is cheap
+    pub fn clear(&mut self) {
+        self.max_radius = 0.;
+        self.cells.iter_mut().for_each(|cell| cell.clear());
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.iter().map(|cell| cell.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.iter().all(|cell| cell.is_empty())
+    }
+
+    pub fn insert(&mut self, center: Complex<f32>, radius: f32, item: T) {
+        let column = self.column(center.re);
+        let row = self.row(center.im);
+        self.max_radius = self.max_radius.max(radius);
+        self.cells[row * self.columns + column].push((center, radius, item));
+    }
+
+    pub fn query(
+        &self,
+        center: Complex<f32>,
+        radius: f32,
+    ) -> impl Iterator<Item = (Complex<f32>, f32, T)> + '_ {
+        let reach = radius + self.max_radius;
+        let columns = self.column(center.re - reach)..=self.column(center.re + reach);
+        let rows = self.row(center.im - reach)..=self.row(center.im + reach);
+
+        rows.flat_map(move |row| {
+            columns
+                .clone()
+                .map(move |column| &self.cells[row * self.columns + column])
+        })
+        .flatten()
+        .filter(move |(position, item_radius, _)| {
+            let distance = radius + item_radius;
+            position.distance_squared(&center) <= distance * distance
+        })
+        .copied()
+    }
+
+    fn column(&self, x: f32) -> usize {
+        ((x / self.cell_size).floor().max(0.) as usize).min(self.columns - 1)
+    }
+
+    fn row(&self, y: f32) -> usize {
+        ((y / self.cell_size).floor().max(0.) as usize).min(self.rows - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmpx;
+
+    fn hits(grid: &SpatialGrid<u32>, center: Complex<f32>, radius: f32) -> Vec<u32> {
+        let mut hits = grid
+            .query(center, radius)
+            .map(|(_, _, id)| id)
+            .collect::<Vec<_>>();
+        hits.sort();
+        hits
+    }
+
+    fn field() -> SpatialGrid<u32> {
+        let mut grid = SpatialGrid::new(384., 448., 32.);
+        grid.insert(cmpx!(3., 3.), 1., 0);
+        // INFO : Outside the field, clamped in the corner cell
+        grid.insert(cmpx!(-5., -2.), 2., 1);
+        grid.insert(cmpx!(40., 5.), 1., 2);
+        grid.insert(cmpx!(30., 100.), 1., 3);
+        grid.insert(cmpx!(34., 100.), 1., 4);
+        grid.insert(cmpx!(38., 100.), 1., 5);
+        grid.insert(cmpx!(200., 200.), 80., 6);
+        grid.insert(cmpx!(383., 447.), 1., 7);
+        grid
+    }
+
+    #[test]
+    fn query_near_the_border() {
+        let grid = field();
+        assert_eq!(grid.len(), 8);
+        assert_eq!(hits(&grid, cmpx!(0., 0.), 4.), vec![0, 1]);
+        assert_eq!(hits(&grid, cmpx!(-3., -1.), 1.), vec![1]);
+        assert_eq!(hits(&grid, cmpx!(390., 450.), 8.), vec![7]);
+        assert!(hits(&grid, cmpx!(390., 450.), 5.).is_empty());
+    }
+
+    #[test]
+    fn query_across_a_cell_boundary() {
+        let grid = field();
+        assert_eq!(hits(&grid, cmpx!(32., 100.), 1.5), vec![3, 4]);
+        assert_eq!(hits(&grid, cmpx!(33., 100.), 4.), vec![3, 4, 5]);
+        assert!(hits(&grid, cmpx!(32., 97.), 1.).is_empty());
+    }
+
+    #[test]
+    fn query_reach_a_body_bigger_than_a_cell() {
+        let grid = field();
+        // INFO : Three cells away from the center but inside its radius
+        assert_eq!(hits(&grid, cmpx!(275., 200.), 2.), vec![6]);
+        assert!(hits(&grid, cmpx!(285., 200.), 2.).is_empty());
+
+        let mut small = SpatialGrid::new(384., 448., 32.);
+        small.insert(cmpx!(200., 200.), 1., 0);
+        assert!(hits(&small, cmpx!(275., 200.), 2.).is_empty());
+
+        small.clear();
+        assert!(small.is_empty());
+    }
+}
@@ -1,7 +1,9 @@
 mod complex;
+mod grid;
 mod vec2;
 
 pub use complex::ComplexExt;
+pub use grid::SpatialGrid;
 use num_complex::Complex;
 use raylib::prelude::*;
 
@@ -0,0 +1,1500 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::SPRITE_SHEETS,
+    components::{
+        AttackMove, BossMove, BossMoves, BulletAction, BulletMotion, BulletSetup, BulletTask,
+        CircleHitbox, Cooldown, Hitpoint, LaserSetup, LaserShape, Sprite,
+    },
+    difficulty::Difficulty,
+    utility::timer::Timer,
+    vec2,
+};
+
+// INFO : Text representation of BossMove / AttackMove so boss attacks can live in asset files,
+// every duration is in second and every speed in pixel per second
+//
+// [[moves]]
+// type = "non_spell"
+// timeout = 120.0
+// hp = 15000.0
+//
+// [moves.attack]
+// type = "circle"
+// sides = 32
+// speed = 140.0
+// rotation_per_fire = 2.0
+// rotation = 12.0
+// cooldown = 1.9
+// bullet = { sheet = "miko_sprite", cell = [0, 3], hitbox = 2.5 }
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BossPattern {
+    pub moves: Vec<BossMoveDef>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BossMoveDef {
+    Spell {
+        name: String,
+        timeout: f32,
+        hp: f32,
+        attack: AttackDef,
+        #[serde(default)]
+        survival: bool,
+    },
+    NonSpell {
+        timeout: f32,
+        hp: f32,
+        attack: AttackDef,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AttackDef {
+    AtPlayer {
+        num: u16,
+        speed: f32,
+        spread: f32,
+        total_shoot: u16,
+        cooldown: f32,
+        bullet: BulletDef,
+    },
+    Circle {
+        sides: u16,
+        speed: f32,
+        rotation_per_fire: f32,
+        rotation: f32,
+        cooldown: f32,
+        bullet: BulletDef,
+    },
+    Spiral {
+        arms: u16,
+        speed: f32,
+        angular_speed: f32,
+        #[serde(default)]
+        rotation: f32,
+        cooldown: f32,
+        #[serde(default)]
+        motion: BulletMotion,
+        bullet: BulletDef,
+    },
+    Arc {
+        num: u16,
+        speed: f32,
+        width: f32,
+        #[serde(default)]
+        angle: f32,
+        #[serde(default)]
+        aimed: bool,
+        cooldown: f32,
+        #[serde(default)]
+        motion: BulletMotion,
+        bullet: BulletDef,
+    },
+    Spray {
+        num: u16,
+        min_speed: f32,
+        max_speed: f32,
+        width: f32,
+        #[serde(default)]
+        angle: f32,
+        #[serde(default)]
+        aimed: bool,
+        cooldown: f32,
+        #[serde(default)]
+        motion: BulletMotion,
+        bullet: BulletDef,
+    },
+    AimedRing {
+        sides: u16,
+        speed: f32,
+        cooldown: f32,
+        #[serde(default)]
+        motion: BulletMotion,
+        bullet: BulletDef,
+    },
+    Stacked {
+        num: u16,
+        layers: u16,
+        min_speed: f32,
+        max_speed: f32,
+        spread: f32,
+        cooldown: f32,
+        #[serde(default)]
+        motion: BulletMotion,
+        bullet: BulletDef,
+    },
+    BurstReaim {
+        sides: u16,
+        speed: f32,
+        retention: f32,
+        delay: f32,
+        reaim_speed: f32,
+        cooldown: f32,
+        bullet: BulletDef,
+    },
+    Laser {
+        #[serde(default = "default_count")]
+        num: u16,
+        #[serde(default)]
+        angle: f32,
+        #[serde(default)]
+        aimed: bool,
+        #[serde(default)]
+        spread: f32,
+        cooldown: f32,
+        laser: LaserSetup,
+    },
+    Multiple {
+        attacks: Vec<AttackDef>,
+    },
+}
+
+fn default_count() -> u16 {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulletDef {
+    pub sheet: String,
+    pub cell: [i32; 2],
+    #[serde(default = "default_cell_size")]
+    pub size: [f32; 2],
+    pub hitbox: f32,
+    #[serde(default)]
+    pub hitbox_offset: [f32; 2],
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tasks: Vec<TaskDef>,
+}
+
+// INFO : Per bullet timeline, at is in second since the bullet was fired
+//
+// tasks = [
+//     { at = 0.5, action = "set_velocity", speed = 0.0 },
+//     { at = 1.0, action = "split", num = 5, spread = 1.2, speed = 180.0, aimed = true },
+// ]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskDef {
+    pub at: f32,
+    #[serde(flatten)]
+    pub action: TaskActionDef,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum TaskActionDef {
+    SetVelocity {
+        speed: f32,
+        #[serde(default)]
+        angle: f32,
+        #[serde(default)]
+        absolute: bool,
+        #[serde(default)]
+        motion: BulletMotion,
+    },
+    Aim {
+        speed: f32,
+        #[serde(default)]
+        motion: BulletMotion,
+    },
+    Split {
+        num: u16,
+        spread: f32,
+        speed: f32,
+        #[serde(default)]
+        aimed: bool,
+        #[serde(default)]
+        motion: BulletMotion,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bullet: Option<Box<BulletDef>>,
+    },
+    SetSprite {
+        sheet: String,
+        cell: [i32; 2],
+        #[serde(default = "default_cell_size")]
+        size: [f32; 2],
+    },
+    SetHitbox {
+        radius: f32,
+        #[serde(default)]
+        offset: [f32; 2],
+    },
+    Despawn,
+}
+
+fn default_cell_size() -> [f32; 2] {
+    [32., 32.]
+}
+
+impl BossPattern {
+    pub fn parse(source: &str) -> io::Result<Self> {
+        let pattern: BossPattern =
+            toml::from_str(source).map_err(|err| invalid_data(err.to_string()))?;
+
+        let errors = pattern.validate();
+        if !errors.is_empty() {
+            return Err(invalid_data(errors.join("\n")));
+        }
+        Ok(pattern)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        Self::parse(&source).map_err(|err| invalid_data(format!("{} : {}", path.display(), err)))
+    }
+
+    pub fn to_toml(&self) -> io::Result<String> {
+        toml::to_string(self).map_err(|err| invalid_data(err.to_string()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_toml()?)
+    }
+
+    // INFO : Report every problem at once instead of stopping on the first one
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.moves.is_empty() {
+            errors.push("moves : a boss need at least one move".to_owned());
+        }
+
+        for (i, boss_move) in self.moves.iter().enumerate() {
+            let path = format!("moves[{}]", i);
+            let (timeout, hp, attack) = match boss_move {
+                BossMoveDef::Spell {
+                    name,
+                    timeout,
+                    hp,
+                    attack,
+                    ..
+                } => {
+                    if name.trim().is_empty() {
+                        errors.push(format!("{}.name : spell card need a name", path));
+                    }
+                    (timeout, hp, attack)
+                }
+                BossMoveDef::NonSpell {
+                    timeout,
+                    hp,
+                    attack,
+                } => (timeout, hp, attack),
+            };
+
+            if !is_positive(*timeout) {
+                errors.push(format!(
+                    "{}.timeout : must be above 0, got {}",
+                    path, timeout
+                ));
+            }
+            if !is_positive(*hp) {
+                errors.push(format!("{}.hp : must be above 0, got {}", path, hp));
+            }
+            attack.validate(&format!("{}.attack", path), &mut errors);
+        }
+
+        errors
+    }
+
+    pub fn to_boss_moves(&self) -> BossMoves {
+        BossMoves(self.moves.iter().map(BossMoveDef::to_boss_move).collect())
+    }
+
+    // INFO : Pattern files are written for Normal, the other difficulties scale them
+    pub fn scaled(&self, difficulty: Difficulty) -> Self {
+        Self {
+            moves: self
+                .moves
+                .iter()
+                .map(|boss_move| boss_move.scaled(difficulty))
+                .collect(),
+        }
+    }
+
+    pub fn spell_names(&self) -> impl Iterator<Item = &str> {
+        self.moves.iter().filter_map(|boss_move| match boss_move {
+            BossMoveDef::Spell { name, .. } => Some(name.as_str()),
+            BossMoveDef::NonSpell { .. } => None,
+        })
+    }
+
+    // INFO : The same boss with nothing but this spell card, for the spell practice
+    pub fn only_spell(&self, spell: &str) -> Option<Self> {
+        let boss_move = self.moves.iter().find(
+            |boss_move| matches!(boss_move, BossMoveDef::Spell { name, .. } if name == spell),
+        )?;
+        Some(Self {
+            moves: vec![boss_move.clone()],
+        })
+    }
+
+    pub fn from_boss_moves(moves: &BossMoves) -> Self {
+        Self {
+            moves: moves.0.iter().map(BossMoveDef::from_boss_move).collect(),
+        }
+    }
+}
+
+// INFO : Factor applied to a pattern written for Normal
+struct DifficultyScale {
+    count: f32,
+    speed: f32,
+    cooldown: f32,
+    hp: f32,
+    timeout: f32,
+}
+
+impl DifficultyScale {
+    fn new(difficulty: Difficulty) -> Self {
+        // INFO : The extra stage patterns are written for Extra directly
+        if difficulty == Difficulty::Extra {
+            return Self::new(Difficulty::Normal);
+        }
+        Self {
+            count: difficulty.difficulty_value(0.5, 1.0, 1.3, 1.6),
+            speed: difficulty.difficulty_value(0.8, 1.0, 1.1, 1.25),
+            cooldown: difficulty.difficulty_value(1.4, 1.0, 0.85, 0.7),
+            hp: difficulty.difficulty_value(0.7, 1.0, 1.2, 1.4),
+            timeout: difficulty.difficulty_value(1.0, 1.0, 1.1, 1.2),
+        }
+    }
+
+    fn count(&self, count: &mut u16) {
+        *count = ((*count as f32 * self.count).round() as u16).max(1);
+    }
+}
+
+impl BossMoveDef {
+    pub fn scaled(&self, difficulty: Difficulty) -> Self {
+        let scale = DifficultyScale::new(difficulty);
+        let mut boss_move = self.clone();
+        match &mut boss_move {
+            BossMoveDef::Spell {
+                timeout,
+                hp,
+                attack,
+                ..
+            }
+            | BossMoveDef::NonSpell {
+                timeout,
+                hp,
+                attack,
+            } => {
+                *timeout *= scale.timeout;
+                *hp *= scale.hp;
+                *attack = attack.scaled(difficulty);
+            }
+        }
+        boss_move
+    }
+
+    pub fn to_boss_move(&self) -> BossMove {
+        match self {
+            BossMoveDef::Spell {
+                name,
+                timeout,
+                hp,
+                attack,
+                survival,
+            } => BossMove::Spells {
+                name: name.clone(),
+                timeout: Timer::new(*timeout, false),
+                hp: Hitpoint::new(*hp),
+                attack: attack.to_attack_move(),
+                survival: *survival,
+            },
+            BossMoveDef::NonSpell {
+                timeout,
+                hp,
+                attack,
+            } => BossMove::NonSpells {
+                timeout: Timer::new(*timeout, false),
+                hp: Hitpoint::new(*hp),
+                attack: attack.to_attack_move(),
+            },
+        }
+    }
+
+    pub fn from_boss_move(boss_move: &BossMove) -> Self {
+        match boss_move {
+            BossMove::Spells {
+                name,
+                timeout,
+                hp,
+                attack,
+                survival,
+            } => BossMoveDef::Spell {
+                name: name.clone(),
+                timeout: timeout.time,
+                hp: hp.max_hp,
+                attack: AttackDef::from_attack_move(attack),
+                survival: *survival,
+            },
+            BossMove::NonSpells {
+                timeout,
+                hp,
+                attack,
+            } => BossMoveDef::NonSpell {
+                timeout: timeout.time,
+                hp: hp.max_hp,
+                attack: AttackDef::from_attack_move(attack),
+            },
+        }
+    }
+}
+
+impl AttackDef {
+    pub fn scaled(&self, difficulty: Difficulty) -> Self {
+        let scale = DifficultyScale::new(difficulty);
+        let mut attack = self.clone();
+        match &mut attack {
+            AttackDef::AtPlayer {
+                num,
+                speed,
+                cooldown,
+                ..
+            }
+            | AttackDef::Arc {
+                num,
+                speed,
+                cooldown,
+                ..
+            } => {
+                scale.count(num);
+                *speed *= scale.speed;
+                *cooldown *= scale.cooldown;
+            }
+            AttackDef::Circle {
+                sides,
+                speed,
+                cooldown,
+                ..
+            }
+            | AttackDef::AimedRing {
+                sides,
+                speed,
+                cooldown,
+                ..
+            } => {
+                scale.count(sides);
+                *speed *= scale.speed;
+                *cooldown *= scale.cooldown;
+            }
+            AttackDef::Spiral {
+                arms,
+                speed,
+                cooldown,
+                ..
+            } => {
+                scale.count(arms);
+                *speed *= scale.speed;
+                *cooldown *= scale.cooldown;
+            }
+            AttackDef::Spray {
+                num,
+                min_speed,
+                max_speed,
+                cooldown,
+                ..
+            }
+            | AttackDef::Stacked {
+                num,
+                min_speed,
+                max_speed,
+                cooldown,
+                ..
+            } => {
+                scale.count(num);
+                *min_speed *= scale.speed;
+                *max_speed *= scale.speed;
+                *cooldown *= scale.cooldown;
+            }
+            AttackDef::BurstReaim {
+                sides,
+                speed,
+                reaim_speed,
+                cooldown,
+                ..
+            } => {
+                scale.count(sides);
+                *speed *= scale.speed;
+                *reaim_speed *= scale.speed;
+                *cooldown *= scale.cooldown;
+            }
+            // INFO : More laser would just wall the player, only the rate change
+            AttackDef::Laser { cooldown, .. } => *cooldown *= scale.cooldown,
+            AttackDef::Multiple { attacks } => {
+                *attacks = attacks
+                    .iter()
+                    .map(|attack| attack.scaled(difficulty))
+                    .collect()
+            }
+        }
+        attack
+    }
+
+    pub fn to_attack_move(&self) -> AttackMove {
+        match self {
+            AttackDef::AtPlayer {
+                num,
+                speed,
+                spread,
+                total_shoot,
+                cooldown,
+                bullet,
+            } => AttackMove::AtPlayer {
+                num: *num,
+                speed: *speed,
+                spread: *spread,
+                total_shoot: *total_shoot,
+                cooldown: Cooldown::new(*cooldown),
+                setup: bullet.to_setup(),
+            },
+            AttackDef::Circle {
+                sides,
+                speed,
+                rotation_per_fire,
+                rotation,
+                cooldown,
+                bullet,
+            } => AttackMove::Circle {
+                sides: *sides,
+                speed: *speed,
+                rotation_per_fire: *rotation_per_fire,
+                rotation: *rotation,
+                cooldown: Cooldown::new(*cooldown),
+                setup: bullet.to_setup(),
+            },
+            AttackDef::Spiral {
+                arms,
+                speed,
+                angular_speed,
+                rotation,
+                cooldown,
+                motion,
+                bullet,
+            } => AttackMove::Spiral {
+                arms: *arms,
+                speed: *speed,
+                angular_speed: *angular_speed,
+                rotation: *rotation,
+                cooldown: Cooldown::new(*cooldown),
+                motion: *motion,
+                setup: bullet.to_setup(),
+            },
+            AttackDef::Arc {
+                num,
+                speed,
+                width,
+                angle,
+                aimed,
+                cooldown,
+                motion,
+                bullet,
+            } => AttackMove::Arc {
+                num: *num,
+                speed: *speed,
+                width: *width,
+                angle: *angle,
+                aimed: *aimed,
+                cooldown: Cooldown::new(*cooldown),
+                motion: *motion,
+                setup: bullet.to_setup(),
+            },
+            AttackDef::Spray {
+                num,
+                min_speed,
+                max_speed,
+                width,
+                angle,
+                aimed,
+                cooldown,
+                motion,
+                bullet,
+            } => AttackMove::Spray {
+                num: *num,
+                min_speed: *min_speed,
+                max_speed: *max_speed,
+                width: *width,
+                angle: *angle,
+                aimed: *aimed,
+                cooldown: Cooldown::new(*cooldown),
+                motion: *motion,
+                setup: bullet.to_setup(),
+            },
+            AttackDef::AimedRing {
+                sides,
+                speed,
+                cooldown,
+                motion,
+                bullet,
+            } => AttackMove::AimedRing {
+                sides: *sides,
+                speed: *speed,
+                cooldown: Cooldown::new(*cooldown),
+                motion: *motion,
+                setup: bullet.to_setup(),
+            },
+            AttackDef::Stacked {
+                num,
+                layers,
+                min_speed,
+                max_speed,
+                spread,
+                cooldown,
+                motion,
+                bullet,
+            } => AttackMove::Stacked {
+                num: *num,
+                layers: *layers,
+                min_speed: *min_speed,
+                max_speed: *max_speed,
+                spread: *spread,
+                cooldown: Cooldown::new(*cooldown),
+                motion: *motion,
+                setup: bullet.to_setup(),
+            },
+            AttackDef::BurstReaim {
+                sides,
+                speed,
+                retention,
+                delay,
+                reaim_speed,
+                cooldown,
+                bullet,
+            } => AttackMove::BurstReaim {
+                sides: *sides,
+                speed: *speed,
+                retention: *retention,
+                delay: *delay,
+                reaim_speed: *reaim_speed,
+                cooldown: Cooldown::new(*cooldown),
+                setup: bullet.to_setup(),
+            },
+            AttackDef::Laser {
+                num,
+                angle,
+                aimed,
+                spread,
+                cooldown,
+                laser,
+            } => AttackMove::Laser {
+                num: *num,
+                angle: *angle,
+                aimed: *aimed,
+                spread: *spread,
+                cooldown: Cooldown::new(*cooldown),
+                laser: *laser,
+            },
+            AttackDef::Multiple { attacks } => {
+                AttackMove::Multiple(attacks.iter().map(AttackDef::to_attack_move).collect())
+            }
+        }
+    }
+
+    pub fn from_attack_move(attack: &AttackMove) -> Self {
+        match attack {
+            AttackMove::AtPlayer {
+                num,
+                speed,
+                spread,
+                total_shoot,
+                cooldown,
+                setup,
+            } => AttackDef::AtPlayer {
+                num: *num,
+                speed: *speed,
+                spread: *spread,
+                total_shoot: *total_shoot,
+                cooldown: cooldown.0.time,
+                bullet: BulletDef::from_setup(setup),
+            },
+            AttackMove::Circle {
+                sides,
+                speed,
+                rotation_per_fire,
+                rotation,
+                cooldown,
+                setup,
+            } => AttackDef::Circle {
+                sides: *sides,
+                speed: *speed,
+                rotation_per_fire: *rotation_per_fire,
+                rotation: *rotation,
+                cooldown: cooldown.0.time,
+                bullet: BulletDef::from_setup(setup),
+            },
+            AttackMove::Spiral {
+                arms,
+                speed,
+                angular_speed,
+                rotation,
+                cooldown,
+                motion,
+                setup,
+            } => AttackDef::Spiral {
+                arms: *arms,
+                speed: *speed,
+                angular_speed: *angular_speed,
+                rotation: *rotation,
+                cooldown: cooldown.0.time,
+                motion: *motion,
+                bullet: BulletDef::from_setup(setup),
+            },
+            AttackMove::Arc {
+                num,
+                speed,
+                width,
+                angle,
+                aimed,
+                cooldown,
+                motion,
+                setup,
+            } => AttackDef::Arc {
+                num: *num,
+                speed: *speed,
+                width: *width,
+                angle: *angle,
+                aimed: *aimed,
+                cooldown: cooldown.0.time,
+                motion: *motion,
+                bullet: BulletDef::from_setup(setup),
+            },
+            AttackMove::Spray {
+                num,
+                min_speed,
+                max_speed,
+                width,
+                angle,
+                aimed,
+                cooldown,
+                motion,
+                setup,
+            } => AttackDef::Spray {
+                num: *num,
+                min_speed: *min_speed,
+                max_speed: *max_speed,
+                width: *width,
+                angle: *angle,
+                aimed: *aimed,
+                cooldown: cooldown.0.time,
+                motion: *motion,
+                bullet: BulletDef::from_setup(setup),
+            },
+            AttackMove::AimedRing {
+                sides,
+                speed,
+                cooldown,
+                motion,
+                setup,
+            } => AttackDef::AimedRing {
+                sides: *sides,
+                speed: *speed,
+                cooldown: cooldown.0.time,
+                motion: *motion,
+                bullet: BulletDef::from_setup(setup),
+            },
+            AttackMove::Stacked {
+                num,
+                layers,
+                min_speed,
+                max_speed,
+                spread,
+                cooldown,
+                motion,
+                setup,
+            } => AttackDef::Stacked {
+                num: *num,
+                layers: *layers,
+                min_speed: *min_speed,
+                max_speed: *max_speed,
+                spread: *spread,
+                cooldown: cooldown.0.time,
+                motion: *motion,
+                bullet: BulletDef::from_setup(setup),
+            },
+            AttackMove::BurstReaim {
+                sides,
+                speed,
+                retention,
+                delay,
+                reaim_speed,
+                cooldown,
+                setup,
+            } => AttackDef::BurstReaim {
+                sides: *sides,
+                speed: *speed,
+                retention: *retention,
+                delay: *delay,
+                reaim_speed: *reaim_speed,
+                cooldown: cooldown.0.time,
+                bullet: BulletDef::from_setup(setup),
+            },
+            AttackMove::Laser {
+                num,
+                angle,
+                aimed,
+                spread,
+                cooldown,
+                laser,
+            } => AttackDef::Laser {
+                num: *num,
+                angle: *angle,
+                aimed: *aimed,
+                spread: *spread,
+                cooldown: cooldown.0.time,
+                laser: *laser,
+            },
+            AttackMove::Multiple(attacks) => AttackDef::Multiple {
+                attacks: attacks.iter().map(AttackDef::from_attack_move).collect(),
+            },
+        }
+    }
+
+    pub(crate) fn validate(&self, path: &str, errors: &mut Vec<String>) {
+        match self {
+            AttackDef::AtPlayer {
+                num,
+                speed,
+                spread,
+                total_shoot,
+                cooldown,
+                bullet,
+            } => {
+                if *num == 0 {
+                    errors.push(format!("{}.num : must fire at least one bullet", path));
+                }
+                if *total_shoot == 0 {
+                    errors.push(format!("{}.total_shoot : must be above 0", path));
+                }
+                if !speed.is_finite() {
+                    errors.push(format!("{}.speed : {} is not a number", path, speed));
+                }
+                if !spread.is_finite() {
+                    errors.push(format!("{}.spread : {} is not a number", path, spread));
+                }
+                validate_cooldown(path, *cooldown, errors);
+                bullet.validate(&format!("{}.bullet", path), errors);
+            }
+            AttackDef::Circle {
+                sides,
+                speed,
+                rotation_per_fire,
+                rotation,
+                cooldown,
+                bullet,
+            } => {
+                if *sides == 0 {
+                    errors.push(format!("{}.sides : must be above 0", path));
+                }
+                if !speed.is_finite() {
+                    errors.push(format!("{}.speed : {} is not a number", path, speed));
+                }
+                if !rotation_per_fire.is_finite() || !rotation.is_finite() {
+                    errors.push(format!("{}.rotation : rotation must be a number", path));
+                }
+                validate_cooldown(path, *cooldown, errors);
+                bullet.validate(&format!("{}.bullet", path), errors);
+            }
+            AttackDef::Spiral {
+                arms,
+                speed,
+                angular_speed,
+                rotation,
+                cooldown,
+                motion,
+                bullet,
+            } => {
+                validate_count(path, "arms", *arms, errors);
+                validate_number(path, "speed", *speed, errors);
+                validate_number(path, "angular_speed", *angular_speed, errors);
+                validate_number(path, "rotation", *rotation, errors);
+                validate_cooldown(path, *cooldown, errors);
+                validate_motion(path, motion, errors);
+                bullet.validate(&format!("{}.bullet", path), errors);
+            }
+            AttackDef::Arc {
+                num,
+                speed,
+                width,
+                angle,
+                cooldown,
+                motion,
+                bullet,
+                ..
+            } => {
+                validate_count(path, "num", *num, errors);
+                validate_number(path, "speed", *speed, errors);
+                validate_number(path, "width", *width, errors);
+                validate_number(path, "angle", *angle, errors);
+                validate_cooldown(path, *cooldown, errors);
+                validate_motion(path, motion, errors);
+                bullet.validate(&format!("{}.bullet", path), errors);
+            }
+            AttackDef::Spray {
+                num,
+                min_speed,
+                max_speed,
+                width,
+                angle,
+                cooldown,
+                motion,
+                bullet,
+                ..
+            } => {
+                validate_count(path, "num", *num, errors);
+                validate_speed_range(path, *min_speed, *max_speed, errors);
+                validate_number(path, "width", *width, errors);
+                validate_number(path, "angle", *angle, errors);
+                validate_cooldown(path, *cooldown, errors);
+                validate_motion(path, motion, errors);
+                bullet.validate(&format!("{}.bullet", path), errors);
+            }
+            AttackDef::AimedRing {
+                sides,
+                speed,
+                cooldown,
+                motion,
+                bullet,
+            } => {
+                validate_count(path, "sides", *sides, errors);
+                validate_number(path, "speed", *speed, errors);
+                validate_cooldown(path, *cooldown, errors);
+                validate_motion(path, motion, errors);
+                bullet.validate(&format!("{}.bullet", path), errors);
+            }
+            AttackDef::Stacked {
+                num,
+                layers,
+                min_speed,
+                max_speed,
+                spread,
+                cooldown,
+                motion,
+                bullet,
+            } => {
+                validate_count(path, "num", *num, errors);
+                validate_count(path, "layers", *layers, errors);
+                validate_speed_range(path, *min_speed, *max_speed, errors);
+                validate_number(path, "spread", *spread, errors);
+                validate_cooldown(path, *cooldown, errors);
+                validate_motion(path, motion, errors);
+                bullet.validate(&format!("{}.bullet", path), errors);
+            }
+            AttackDef::BurstReaim {
+                sides,
+                speed,
+                retention,
+                delay,
+                reaim_speed,
+                cooldown,
+                bullet,
+            } => {
+                validate_count(path, "sides", *sides, errors);
+                validate_number(path, "speed", *speed, errors);
+                validate_retention(path, *retention, errors);
+                if !is_positive(*delay) {
+                    errors.push(format!("{}.delay : must be above 0, got {}", path, delay));
+                }
+                validate_number(path, "reaim_speed", *reaim_speed, errors);
+                validate_cooldown(path, *cooldown, errors);
+                bullet.validate(&format!("{}.bullet", path), errors);
+            }
+            AttackDef::Laser {
+                num,
+                angle,
+                spread,
+                cooldown,
+                laser,
+                ..
+            } => {
+                validate_count(path, "num", *num, errors);
+                validate_number(path, "angle", *angle, errors);
+                validate_number(path, "spread", *spread, errors);
+                validate_cooldown(path, *cooldown, errors);
+                validate_laser(&format!("{}.laser", path), laser, errors);
+            }
+            AttackDef::Multiple { attacks } => {
+                if attacks.is_empty() {
+                    errors.push(format!(
+                        "{}.attacks : must contain at least one attack",
+                        path
+                    ));
+                }
+                for (i, attack) in attacks.iter().enumerate() {
+                    attack.validate(&format!("{}.attacks[{}]", path, i), errors);
+                }
+            }
+        }
+    }
+}
+
+fn validate_count(path: &str, field: &str, count: u16, errors: &mut Vec<String>) {
+    if count == 0 {
+        errors.push(format!("{}.{} : must be above 0", path, field));
+    }
+}
+
+fn validate_number(path: &str, field: &str, value: f32, errors: &mut Vec<String>) {
+    if !value.is_finite() {
+        errors.push(format!("{}.{} : {} is not a number", path, field, value));
+    }
+}
+
+fn validate_speed_range(path: &str, min: f32, max: f32, errors: &mut Vec<String>) {
+    validate_number(path, "min_speed", min, errors);
+    validate_number(path, "max_speed", max, errors);
+    if min > max {
+        errors.push(format!(
+            "{}.min_speed : {} is above max_speed {}",
+            path, min, max
+        ));
+    }
+}
+
+fn validate_retention(path: &str, retention: f32, errors: &mut Vec<String>) {
+    if !(0. ..1.).contains(&retention) {
+        errors.push(format!(
+            "{}.retention : must be between 0 and 1, got {}",
+            path, retention
+        ));
+    }
+}
+
+fn validate_motion(path: &str, motion: &BulletMotion, errors: &mut Vec<String>) {
+    let path = format!("{}.motion", path);
+    match motion {
+        BulletMotion::Linear => {}
+        BulletMotion::Accelerated { along, side } => {
+            validate_number(&path, "along", *along, errors);
+            validate_number(&path, "side", *side, errors);
+        }
+        BulletMotion::Asymptotic { boost, retention } => {
+            validate_number(&path, "boost", *boost, errors);
+            validate_retention(&path, *retention, errors);
+        }
+        BulletMotion::Towards { attraction } => {
+            validate_number(&path, "attraction", *attraction, errors);
+        }
+    }
+}
+
+pub(crate) fn validate_laser(path: &str, laser: &LaserSetup, errors: &mut Vec<String>) {
+    if !is_positive(laser.width) {
+        errors.push(format!(
+            "{}.width : must be above 0, got {}",
+            path, laser.width
+        ));
+    }
+    if !is_positive(laser.duration) {
+        errors.push(format!(
+            "{}.duration : must be above 0, got {}",
+            path, laser.duration
+        ));
+    }
+    if !(laser.warning >= 0. && laser.grow >= 0.) {
+        errors.push(format!(
+            "{}.warning : warning and grow can't be negative",
+            path
+        ));
+    }
+    match &laser.shape {
+        LaserShape::Straight {
+            length,
+            angular_speed,
+        } => {
+            if !is_positive(*length) {
+                errors.push(format!(
+                    "{}.shape.length : must be above 0, got {}",
+                    path, length
+                ));
+            }
+            validate_number(path, "shape.angular_speed", *angular_speed, errors);
+        }
+        LaserShape::Curvy {
+            speed,
+            length,
+            motion,
+        } => {
+            validate_number(path, "shape.speed", *speed, errors);
+            if *length < 2 {
+                errors.push(format!(
+                    "{}.shape.length : a curvy laser need at least 2 point",
+                    path
+                ));
+            }
+            validate_motion(&format!("{}.shape", path), motion, errors);
+        }
+    }
+}
+
+fn validate_cooldown(path: &str, cooldown: f32, errors: &mut Vec<String>) {
+    if !is_positive(cooldown) {
+        errors.push(format!(
+            "{}.cooldown : must be above 0, got {}",
+            path, cooldown
+        ));
+    }
+}
+
+impl BulletDef {
+    pub fn to_setup(&self) -> BulletSetup {
+        BulletSetup(
+            to_sprite(&self.sheet, self.cell, self.size),
+            CircleHitbox::new(
+                self.hitbox,
+                vec2!(self.hitbox_offset[0], self.hitbox_offset[1]),
+            ),
+            self.tasks.iter().map(TaskDef::to_task).collect(),
+        )
+    }
+
+    pub fn from_setup(setup: &BulletSetup) -> Self {
+        let BulletSetup(sprite, hitbox, tasks) = setup;
+        let (sheet, cell, size) = from_sprite(sprite);
+        Self {
+            sheet,
+            cell,
+            size,
+            hitbox: hitbox.radius,
+            hitbox_offset: [hitbox.offset.x, hitbox.offset.y],
+            tasks: tasks.iter().map(TaskDef::from_task).collect(),
+        }
+    }
+
+    fn validate(&self, path: &str, errors: &mut Vec<String>) {
+        validate_sprite(path, &self.sheet, self.cell, self.size, errors);
+        if !is_positive(self.hitbox) {
+            errors.push(format!(
+                "{}.hitbox : must be above 0, got {}",
+                path, self.hitbox
+            ));
+        }
+        for (i, task) in self.tasks.iter().enumerate() {
+            task.validate(&format!("{}.tasks[{}]", path, i), errors);
+        }
+    }
+}
+
+impl TaskDef {
+    pub fn to_task(&self) -> BulletTask {
+        let action = match &self.action {
+            TaskActionDef::SetVelocity {
+                speed,
+                angle,
+                absolute,
+                motion,
+            } => BulletAction::SetVelocity {
+                speed: *speed,
+                angle: *angle,
+                absolute: *absolute,
+                motion: *motion,
+            },
+            TaskActionDef::Aim { speed, motion } => BulletAction::Aim {
+                speed: *speed,
+                motion: *motion,
+            },
+            TaskActionDef::Split {
+                num,
+                spread,
+                speed,
+                aimed,
+                motion,
+                bullet,
+            } => BulletAction::Split {
+                num: *num,
+                spread: *spread,
+                speed: *speed,
+                aimed: *aimed,
+                motion: *motion,
+                setup: bullet.as_ref().map(|bullet| Box::new(bullet.to_setup())),
+            },
+            TaskActionDef::SetSprite { sheet, cell, size } => {
+                BulletAction::SetSprite(to_sprite(sheet, *cell, *size))
+            }
+            TaskActionDef::SetHitbox { radius, offset } => {
+                BulletAction::SetHitbox(CircleHitbox::new(*radius, vec2!(offset[0], offset[1])))
+            }
+            TaskActionDef::Despawn => BulletAction::Despawn,
+        };
+        BulletTask {
+            at: self.at,
+            action,
+        }
+    }
+
+    pub fn from_task(task: &BulletTask) -> Self {
+        let action = match &task.action {
+            BulletAction::SetVelocity {
+                speed,
+                angle,
+                absolute,
+                motion,
+            } => TaskActionDef::SetVelocity {
+                speed: *speed,
+                angle: *angle,
+                absolute: *absolute,
+                motion: *motion,
+            },
+            BulletAction::Aim { speed, motion } => TaskActionDef::Aim {
+                speed: *speed,
+                motion: *motion,
+            },
+            BulletAction::Split {
+                num,
+                spread,
+                speed,
+                aimed,
+                motion,
+                setup,
+            } => TaskActionDef::Split {
+                num: *num,
+                spread: *spread,
+                speed: *speed,
+                aimed: *aimed,
+                motion: *motion,
+                bullet: setup
+                    .as_ref()
+                    .map(|setup| Box::new(BulletDef::from_setup(setup))),
+            },
+            BulletAction::SetSprite(sprite) => {
+                let (sheet, cell, size) = from_sprite(sprite);
+                TaskActionDef::SetSprite { sheet, cell, size }
+            }
+            BulletAction::SetHitbox(hitbox) => TaskActionDef::SetHitbox {
+                radius: hitbox.radius,
+                offset: [hitbox.offset.x, hitbox.offset.y],
+            },
+            BulletAction::Despawn => TaskActionDef::Despawn,
+        };
+        Self {
+            at: task.at,
+            action,
+        }
+    }
+
+    fn validate(&self, path: &str, errors: &mut Vec<String>) {
+        if !self.at.is_finite() || self.at < 0. {
+            errors.push(format!("{}.at : {} is not a valid time", path, self.at));
+        }
+        match &self.action {
+            TaskActionDef::SetVelocity {
+                speed,
+                angle,
+                motion,
+                ..
+            } => {
+                validate_number(path, "speed", *speed, errors);
+                validate_number(path, "angle", *angle, errors);
+                validate_motion(path, motion, errors);
+            }
+            TaskActionDef::Aim { speed, motion } => {
+                validate_number(path, "speed", *speed, errors);
+                validate_motion(path, motion, errors);
+            }
+            TaskActionDef::Split {
+                num,
+                spread,
+                speed,
+                motion,
+                bullet,
+                ..
+            } => {
+                validate_count(path, "num", *num, errors);
+                validate_number(path, "spread", *spread, errors);
+                validate_number(path, "speed", *speed, errors);
+                validate_motion(path, motion, errors);
+                if let Some(bullet) = bullet {
+                    bullet.validate(&format!("{}.bullet", path), errors);
+                }
+            }
+            TaskActionDef::SetSprite { sheet, cell, size } => {
+                validate_sprite(path, sheet, *cell, *size, errors);
+            }
+            TaskActionDef::SetHitbox { radius, .. } => {
+                if !is_positive(*radius) {
+                    errors.push(format!("{}.radius : must be above 0, got {}", path, radius));
+                }
+            }
+            TaskActionDef::Despawn => {}
+        }
+    }
+}
+
+// INFO : Sprite only keep a &'static str, so reuse the name from SPRITE_SHEETS
+fn find_sheet(sheet: &str) -> Option<&'static str> {
+    SPRITE_SHEETS
+        .iter()
+        .find(|(name, _)| *name == sheet)
+        .map(|(name, _)| *name)
+}
+
+fn to_sprite(sheet: &str, cell: [i32; 2], size: [f32; 2]) -> Sprite {
+    let name = find_sheet(sheet).expect("[-] Pattern must be validated before use!");
+    Sprite::new(name, cell[0], cell[1], size[0], size[1])
+}
+
+fn from_sprite(sprite: &Sprite) -> (String, [i32; 2], [f32; 2]) {
+    (
+        sprite.name.to_owned(),
+        [(sprite.src.x / 32.) as i32, (sprite.src.y / 32.) as i32],
+        [sprite.src.width, sprite.src.height],
+    )
+}
+
+fn validate_sprite(
+    path: &str,
+    sheet: &str,
+    cell: [i32; 2],
+    size: [f32; 2],
+    errors: &mut Vec<String>,
+) {
+    if find_sheet(sheet).is_none() {
+        let known = SPRITE_SHEETS
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        errors.push(format!(
+            "{}.sheet : unknown sprite sheet \"{}\" (expected one of {})",
+            path, sheet, known
+        ));
+    }
+    if cell[0] < 0 || cell[1] < 0 {
+        errors.push(format!("{}.cell : {:?} can't be negative", path, cell));
+    }
+    if !is_positive(size[0]) || !is_positive(size[1]) {
+        errors.push(format!("{}.size : {:?} must be above 0", path, size));
+    }
+}
+
+fn is_positive(value: f32) -> bool {
+    value.is_finite() && value > 0.
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPELL: &str = r#"
+[[moves]]
+type = "spell"
+name = "Test Sign 'Circle'"
+timeout = 60.0
+hp = 1000.0
+
+[moves.attack]
+type = "circle"
+sides = 20
+speed = 100.0
+rotation_per_fire = 2.0
+rotation = 12.0
+cooldown = 1.0
+bullet = { sheet = "miko_sprite", cell = [0, 3], hitbox = 2.5 }
+"#;
+
+    fn errors(source: &str) -> Vec<String> {
+        let pattern: BossPattern = toml::from_str(source).unwrap();
+        pattern.validate()
+    }
+
+    fn circle(pattern: &BossPattern) -> (f32, f32, u16, f32, f32) {
+        match &pattern.moves[0] {
+            BossMoveDef::Spell {
+                timeout,
+                hp,
+                attack:
+                    AttackDef::Circle {
+                        sides,
+                        speed,
+                        cooldown,
+                        ..
+                    },
+                ..
+            } => (*timeout, *hp, *sides, *speed, *cooldown),
+            boss_move => panic!("unexpected move {:?}", boss_move),
+        }
+    }
+
+    #[test]
+    fn valid_pattern_parse() {
+        let pattern = BossPattern::parse(SPELL).unwrap();
+        assert_eq!(
+            pattern.spell_names().collect::<Vec<_>>(),
+            vec!["Test Sign 'Circle'"]
+        );
+    }
+
+    #[test]
+    fn unknown_sprite_sheet_is_reported() {
+        let errors = errors(&SPELL.replace("miko_sprite", "nope_sprite"));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("moves[0].attack.bullet.sheet"));
+        assert!(BossPattern::parse(&SPELL.replace("miko_sprite", "nope_sprite")).is_err());
+    }
+
+    #[test]
+    fn zero_and_negative_cooldown_are_reported() {
+        for cooldown in ["0.0", "-1.0"] {
+            let errors =
+                errors(&SPELL.replace("cooldown = 1.0", &format!("cooldown = {}", cooldown)));
+            assert_eq!(errors.len(), 1);
+            assert!(errors[0].starts_with("moves[0].attack.cooldown"));
+        }
+    }
+
+    #[test]
+    fn spell_card_without_name_is_reported() {
+        let errors = errors(&SPELL.replace("Test Sign 'Circle'", " "));
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("moves[0].name"));
+    }
+
+    #[test]
+    fn unknown_spell_card_is_not_found() {
+        let pattern = BossPattern::parse(SPELL).unwrap();
+        assert!(pattern.only_spell("Test Sign 'Circle'").is_some());
+        assert!(pattern.only_spell("Test Sign 'Square'").is_none());
+    }
+
+    #[test]
+    fn every_boss_pattern_asset_is_valid() {
+        for path in [
+            "./assets/patterns/miko.toml",
+            "./assets/patterns/miko_extra.toml",
+        ] {
+            assert!(BossPattern::load(path).is_ok(), "{}", path);
+        }
+    }
+
+    #[test]
+    fn scaled_follow_the_difficulty() {
+        let pattern = BossPattern::parse(SPELL).unwrap();
+        let normal = circle(&pattern);
+        assert_eq!(circle(&pattern.scaled(Difficulty::Normal)), normal);
+        // INFO : Extra patterns are written for Extra and are not scaled
+        assert_eq!(circle(&pattern.scaled(Difficulty::Extra)), normal);
+
+        let easy = circle(&pattern.scaled(Difficulty::Easy));
+        let hard = circle(&pattern.scaled(Difficulty::Hard));
+        let lunatic = circle(&pattern.scaled(Difficulty::Lunatic));
+        assert_eq!(easy, (60., 700., 10, 80., 1.4));
+        assert_eq!(hard.2, 26);
+        assert_eq!(lunatic.2, 32);
+        for (lower, higher) in [(easy, normal), (normal, hard), (hard, lunatic)] {
+            assert!(lower.0 <= higher.0);
+            assert!(lower.1 < higher.1);
+            assert!(lower.2 < higher.2);
+            assert!(lower.3 < higher.3);
+            assert!(lower.4 > higher.4);
+        }
+    }
+}
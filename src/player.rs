@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerKind {
+    ReimuA, // Persuasion Needle / Fantasy Seal
+    ReimuB, // Homing Amulet / Evil Sealing Circle
+}
+
+impl PlayerKind {
+    pub fn id(&self) -> u8 {
+        match self {
+            PlayerKind::ReimuA => 0,
+            PlayerKind::ReimuB => 1,
+        }
+    }
+
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(PlayerKind::ReimuA),
+            1 => Some(PlayerKind::ReimuB),
+            _ => None,
+        }
+    }
+}
+
+impl AsRef<str> for PlayerKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            PlayerKind::ReimuA => "Reimu A",
+            PlayerKind::ReimuB => "Reimu B",
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use crate::difficulty::Difficulty;
+
+const RANK_START: f32 = 0.5;
+// INFO : Rise per second only by surviving, and at full power on top of it
+const RANK_PER_SECOND: f32 = 0.002;
+const RANK_PER_SECOND_FULL_POWER: f32 = 0.002;
+const RANK_PER_GRAZE: f32 = 0.0005;
+const RANK_PER_SCORE_STEP: f32 = 0.01;
+const RANK_SCORE_STEP: u64 = 1_000_000;
+const RANK_DEATH: f32 = 0.15;
+const RANK_BOMB: f32 = 0.05;
+
+// INFO : Hidden difficulty on top of the chosen one, 0 is the easiest the difficulty allow
+// and 1 the hardest. It climb while the player is doing well and drop on death or bomb
+#[derive(Debug, Clone, Copy)]
+pub struct Rank {
+    pub value: f32,
+    // INFO : Score already counted toward the rank, in RANK_SCORE_STEP
+    score_steps: u64,
+}
+
+// INFO : What the patterns apply to the bullet they fire
+#[derive(Debug, Clone, Copy)]
+pub struct RankModifier {
+    pub speed: f32,
+    pub density: f32,
+}
+
+impl Default for Rank {
+    fn default() -> Self {
+        Self {
+            value: RANK_START,
+            score_steps: 0,
+        }
+    }
+}
+
+impl Rank {
+    pub fn new(value: f32, score: u64) -> Self {
+        Self {
+            value: value.clamp(0., 1.),
+            score_steps: score / RANK_SCORE_STEP,
+        }
+    }
+
+    pub fn raise(&mut self, amount: f32) {
+        self.value = (self.value + amount).clamp(0., 1.);
+    }
+
+    pub fn lower(&mut self, amount: f32) {
+        self.raise(-amount);
+    }
+
+    pub fn update(&mut self, power: f32, max_power: f32, score: u64, delta: f32) {
+        let power = (power / max_power).clamp(0., 1.);
+        self.raise((RANK_PER_SECOND + RANK_PER_SECOND_FULL_POWER * power) * delta);
+
+        let steps = score / RANK_SCORE_STEP;
+        if steps > self.score_steps {
+            self.raise(RANK_PER_SCORE_STEP * (steps - self.score_steps) as f32);
+            self.score_steps = steps;
+        }
+    }
+
+    pub fn graze(&mut self) {
+        self.raise(RANK_PER_GRAZE);
+    }
+
+    pub fn death(&mut self) {
+        self.lower(RANK_DEATH);
+    }
+
+    pub fn bomb(&mut self) {
+        self.lower(RANK_BOMB);
+    }
+
+    pub fn modifier(&self, difficulty: Difficulty) -> RankModifier {
+        let (min, max) = difficulty.rank_range();
+        let scale = min + (max - min) * self.value;
+        RankModifier {
+            speed: scale,
+            density: scale,
+        }
+    }
+}
+
+impl RankModifier {
+    pub fn count(&self, count: u16) -> u16 {
+        ((count as f32 * self.density).round() as u16).max(1)
+    }
+}
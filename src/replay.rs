@@ -0,0 +1,232 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    controls::ActionState, difficulty::Difficulty, player::PlayerKind, rank::Rank,
+    score::ScoreData, stage::STAGES,
+};
+
+pub const REPLAY_DIR: &str = "./replays";
+pub const REPLAY_EXTENSION: &str = "rpy";
+
+const MAGIC: &[u8; 4] = b"TUMR";
+// INFO : Bump this everytime the layout or the simulation changes in a way old replay desync
+pub const REPLAY_VERSION: u16 = 7;
+
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub seed: u64,
+    // INFO : Index in stage::STAGES
+    pub stage: u8,
+    pub start: ScoreData,
+    // INFO : One input per simulation tick
+    pub frames: Vec<ActionState>,
+    // INFO : Rank sampled every RANK_SAMPLE_TICKS, only for balancing, playback recompute it
+    pub rank: Vec<f32>,
+}
+
+pub const RANK_SAMPLE_TICKS: u64 = 60;
+
+impl Replay {
+    pub fn new(seed: u64, stage: u8, start: ScoreData) -> Self {
+        Self {
+            seed,
+            stage,
+            start,
+            frames: Vec::new(),
+            rank: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, input: ActionState) {
+        self.frames.push(input);
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(48 + self.frames.len() * 4);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.push(self.stage);
+
+        bytes.push(self.start.difficulty.id());
+        bytes.push(self.start.player.id());
+        bytes.extend_from_slice(&self.start.graze.to_le_bytes());
+        bytes.extend_from_slice(&self.start.score.to_le_bytes());
+        bytes.extend_from_slice(&self.start.power.to_le_bytes());
+        bytes.extend_from_slice(&self.start.value.to_le_bytes());
+        bytes.extend_from_slice(&self.start.life.to_le_bytes());
+        bytes.extend_from_slice(&self.start.spell.to_le_bytes());
+        bytes.push(self.start.bomb_pieces);
+        bytes.push(self.start.life_pieces);
+        bytes.extend_from_slice(&self.start.point_items.to_le_bytes());
+        bytes.push(self.start.continues);
+        bytes.push(self.start.practice as u8);
+        bytes.extend_from_slice(&self.start.rank.value.to_le_bytes());
+
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        for frame in &self.frames {
+            bytes.extend_from_slice(&frame.down.to_le_bytes());
+            bytes.extend_from_slice(&frame.pressed.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&(self.rank.len() as u32).to_le_bytes());
+        for rank in &self.rank {
+            bytes.extend_from_slice(&rank.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Self> {
+        let mut reader = ByteReader { bytes, cursor: 0 };
+
+        if reader.take(4)? != MAGIC {
+            return Err(invalid_data("not a replay file"));
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != REPLAY_VERSION {
+            return Err(invalid_data(&format!(
+                "unsupported replay version {} (expected {})",
+                version, REPLAY_VERSION
+            )));
+        }
+        let seed = u64::from_le_bytes(reader.array()?);
+        let stage = reader.array::<1>()?[0];
+        if stage as usize >= STAGES.len() {
+            return Err(invalid_data("unknown stage"));
+        }
+
+        let difficulty = Difficulty::from_id(reader.array::<1>()?[0])
+            .ok_or_else(|| invalid_data("unknown difficulty"))?;
+        let player = PlayerKind::from_id(reader.array::<1>()?[0])
+            .ok_or_else(|| invalid_data("unknown player"))?;
+        let mut start = ScoreData::new(difficulty, player);
+        start.graze = i32::from_le_bytes(reader.array()?);
+        start.score = u64::from_le_bytes(reader.array()?);
+        start.power = f32::from_le_bytes(reader.array()?);
+        start.value = u64::from_le_bytes(reader.array()?);
+        start.life = i8::from_le_bytes(reader.array()?);
+        start.spell = i8::from_le_bytes(reader.array()?);
+        start.bomb_pieces = reader.array::<1>()?[0];
+        start.life_pieces = reader.array::<1>()?[0];
+        start.point_items = u32::from_le_bytes(reader.array()?);
+        start.continues = reader.array::<1>()?[0];
+        start.practice = reader.array::<1>()?[0] != 0;
+        start.rank = Rank::new(f32::from_le_bytes(reader.array()?), start.score);
+
+        let len = u32::from_le_bytes(reader.array()?) as usize;
+        let mut frames = Vec::with_capacity(len.min(bytes.len() / 4));
+        for _ in 0..len {
+            let down = u16::from_le_bytes(reader.array()?);
+            let pressed = u16::from_le_bytes(reader.array()?);
+            frames.push(ActionState::new(down, pressed));
+        }
+
+        let len = u32::from_le_bytes(reader.array()?) as usize;
+        let mut rank = Vec::with_capacity(len.min(bytes.len() / 4));
+        for _ in 0..len {
+            rank.push(f32::from_le_bytes(reader.array()?));
+        }
+
+        Ok(Self {
+            seed,
+            stage,
+            start,
+            frames,
+            rank,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        if let Some(parent) = path.as_ref().parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_bytes())
+    }
+
+    // INFO : Save to the replay directory with timestamped name
+    pub fn save_new(&self) -> io::Result<PathBuf> {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or(0);
+        let path = Path::new(REPLAY_DIR).join(format!("replay-{}.{}", time, REPLAY_EXTENSION));
+        self.save(&path)?;
+        Ok(path)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn list() -> Vec<PathBuf> {
+        let mut replays = match fs::read_dir(REPLAY_DIR) {
+            Ok(dir) => dir
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|extension| extension == REPLAY_EXTENSION)
+                })
+                .collect::<Vec<_>>(),
+            Err(_) => Vec::new(),
+        };
+        replays.sort();
+        replays
+    }
+}
+
+#[derive(Debug)]
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    cursor: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, cursor: 0 }
+    }
+
+    pub fn next_frame(&mut self) -> Option<ActionState> {
+        let frame = self.replay.frames.get(self.cursor).copied();
+        self.cursor += 1;
+        frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.cursor >= self.replay.frames.len()
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> io::Result<&'a [u8]> {
+        if self.cursor + len > self.bytes.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "replay file is truncated",
+            ));
+        }
+        let slice = &self.bytes[self.cursor..self.cursor + len];
+        self.cursor += len;
+        Ok(slice)
+    }
+
+    fn array<const N: usize>(&mut self) -> io::Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
@@ -0,0 +1,191 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{difficulty::Difficulty, player::PlayerKind};
+
+pub const SAVE_PATH: &str = "./save.toml";
+pub const SCORES_PER_TABLE: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    pub difficulty: Difficulty,
+    pub player: PlayerKind,
+    pub score: u64,
+}
+
+// INFO : Furthest stage started in a regular run, the stage practice only offer those
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StageProgress {
+    pub difficulty: Difficulty,
+    pub player: PlayerKind,
+    pub stage: u8,
+}
+
+// INFO : Best score of a stage practice, kept apart from the regular score table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PracticeScore {
+    pub difficulty: Difficulty,
+    pub player: PlayerKind,
+    pub stage: u8,
+    pub score: u64,
+}
+
+// INFO : Progress kept between runs, there is one score table per difficulty and character
+// so Extra keep its own
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SaveData {
+    #[serde(default)]
+    pub extra_unlocked: bool,
+    // INFO : Character of the last regular run, the spell practice open on it
+    #[serde(default)]
+    pub last_player: Option<PlayerKind>,
+    #[serde(default)]
+    scores: Vec<ScoreEntry>,
+    #[serde(default)]
+    reached: Vec<StageProgress>,
+    #[serde(default)]
+    practice: Vec<PracticeScore>,
+}
+
+impl SaveData {
+    pub fn parse(source: &str) -> io::Result<Self> {
+        toml::from_str(source).map_err(|err| invalid_data(err.to_string()))
+    }
+
+    // INFO : First launch, nothing saved yet
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(source) => Self::parse(&source),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let source = toml::to_string(self).map_err(|err| invalid_data(err.to_string()))?;
+        fs::write(path, source)
+    }
+
+    // INFO : Highest first
+    pub fn scores(
+        &self,
+        difficulty: Difficulty,
+        player: PlayerKind,
+    ) -> impl Iterator<Item = &ScoreEntry> {
+        self.scores
+            .iter()
+            .filter(move |entry| entry.difficulty == difficulty && entry.player == player)
+    }
+
+    pub fn high_score(&self, difficulty: Difficulty, player: PlayerKind) -> Option<u64> {
+        self.scores(difficulty, player)
+            .map(|entry| entry.score)
+            .max()
+    }
+
+    // INFO : Only the best SCORES_PER_TABLE of a table are kept
+    pub fn record_score(&mut self, difficulty: Difficulty, player: PlayerKind, score: u64) {
+        self.scores.push(ScoreEntry {
+            difficulty,
+            player,
+            score,
+        });
+        self.scores
+            .sort_by_key(|entry| std::cmp::Reverse(entry.score));
+
+        let mut kept = 0;
+        self.scores.retain(|entry| {
+            if entry.difficulty != difficulty || entry.player != player {
+                return true;
+            }
+            kept += 1;
+            kept <= SCORES_PER_TABLE
+        });
+    }
+
+    // INFO : Number of stages started at least once, from the first one
+    pub fn stages_reached(&self, difficulty: Difficulty, player: PlayerKind) -> usize {
+        self.reached
+            .iter()
+            .find(|progress| progress.difficulty == difficulty && progress.player == player)
+            .map(|progress| progress.stage as usize + 1)
+            .unwrap_or(0)
+    }
+
+    pub fn reach_stage(&mut self, difficulty: Difficulty, player: PlayerKind, stage: usize) {
+        let stage = stage as u8;
+        match self
+            .reached
+            .iter_mut()
+            .find(|progress| progress.difficulty == difficulty && progress.player == player)
+        {
+            Some(progress) => progress.stage = progress.stage.max(stage),
+            None => self.reached.push(StageProgress {
+                difficulty,
+                player,
+                stage,
+            }),
+        }
+    }
+
+    pub fn practice_high_score(
+        &self,
+        difficulty: Difficulty,
+        player: PlayerKind,
+        stage: usize,
+    ) -> Option<u64> {
+        self.practice
+            .iter()
+            .find(|entry| {
+                entry.difficulty == difficulty
+                    && entry.player == player
+                    && entry.stage as usize == stage
+            })
+            .map(|entry| entry.score)
+    }
+
+    pub fn record_practice_score(
+        &mut self,
+        difficulty: Difficulty,
+        player: PlayerKind,
+        stage: usize,
+        score: u64,
+    ) {
+        let stage = stage as u8;
+        match self.practice.iter_mut().find(|entry| {
+            entry.difficulty == difficulty && entry.player == player && entry.stage == stage
+        }) {
+            Some(entry) => entry.score = entry.score.max(score),
+            None => self.practice.push(PracticeScore {
+                difficulty,
+                player,
+                stage,
+                score,
+            }),
+        }
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_player_is_saved() {
+        let save = SaveData::parse("").unwrap();
+        assert_eq!(save.last_player, None);
+
+        let save = SaveData {
+            last_player: Some(PlayerKind::ReimuB),
+            ..SaveData::default()
+        };
+        let source = toml::to_string(&save).unwrap();
+        let loaded = SaveData::parse(&source).unwrap();
+        assert_eq!(loaded.last_player, Some(PlayerKind::ReimuB));
+    }
+}
@@ -1,14 +1,17 @@
 use crate::{
     controls::Action,
     difficulty::Difficulty,
+    player::PlayerKind,
     score::ScoreData,
-    stage::stage1,
+    stage::first_stage,
     state::State,
     ui::character_selection::{Character, DifficultyChoice, ShotType},
 };
 use raylib::prelude::*;
 
-use super::{main_menu::MainMenu, stage_view::StageView, Scene};
+use super::{
+    main_menu::MainMenu, practice_selection::PracticeSelection, stage_view::StageView, Scene,
+};
 
 #[derive(Debug)]
 enum CurrentSelection {
@@ -16,9 +19,19 @@ enum CurrentSelection {
     Character,
 }
 
+// INFO : What the selection lead to, Extra has a single difficulty so its difficulty menu
+// is skipped
+#[derive(Debug, PartialEq)]
+enum RunMode {
+    Main,
+    Extra,
+    Practice,
+}
+
 #[derive(Debug)]
 pub struct CharacterSelection {
     current_menu: CurrentSelection,
+    mode: RunMode,
     pub difficulty_selected: usize,
     pub character_select: [usize; 2],
 
@@ -30,19 +43,20 @@ impl CharacterSelection {
     pub fn new() -> Self {
         Self {
             current_menu: CurrentSelection::Difficulty,
-            difficulty_selected: 0,
+            mode: RunMode::Main,
+            difficulty_selected: 1,
             character_select: [0, 0],
             difficulty_choices: [
-                DifficultyChoice::new("Easy", "New to STG", true),
+                DifficultyChoice::new("Easy", "New to STG", false),
                 DifficultyChoice::new("Normal", "Most People", false),
-                DifficultyChoice::new("Hard", "Arcade Difficulty", true),
-                DifficultyChoice::new("Lunatic", "Weird People", true),
+                DifficultyChoice::new("Hard", "Arcade Difficulty", false),
+                DifficultyChoice::new("Lunatic", "Weird People", false),
             ],
             character_choices: [
                 Character::new(
                     "Hakurei Reimu",
                     ShotType::new("Percission Needle", "Fantasy Seal", false),
-                    ShotType::new("Homing Amulet", "Evil Sealing Circle", true),
+                    ShotType::new("Homing Amulet", "Evil Sealing Circle", false),
                     "reimu_char",
                     false,
                 ),
@@ -56,6 +70,21 @@ impl CharacterSelection {
             ],
         }
     }
+
+    pub fn extra() -> Self {
+        Self {
+            current_menu: CurrentSelection::Character,
+            mode: RunMode::Extra,
+            ..Self::new()
+        }
+    }
+
+    pub fn practice() -> Self {
+        Self {
+            mode: RunMode::Practice,
+            ..Self::new()
+        }
+    }
 }
 
 impl Scene for CharacterSelection {
@@ -64,7 +93,10 @@ impl Scene for CharacterSelection {
     }
 
     fn clean_up(&mut self, state: &mut State) {
-        state.audio.stop_bgm();
+        // INFO : The practice menu keep the title music
+        if self.mode != RunMode::Practice {
+            state.audio.stop_bgm();
+        }
     }
 
     fn update(&mut self, d: &mut RaylibDrawHandle, state: &mut State) {
@@ -74,6 +106,9 @@ impl Scene for CharacterSelection {
             state.audio.select_sfx.play(state.sfx_volume);
             match self.current_menu {
                 CurrentSelection::Difficulty => state.change_scene(Box::new(MainMenu::new())),
+                CurrentSelection::Character if self.mode == RunMode::Extra => {
+                    state.change_scene(Box::new(MainMenu::new()))
+                }
                 CurrentSelection::Character => self.current_menu = CurrentSelection::Difficulty,
             }
         }
@@ -83,14 +118,28 @@ impl Scene for CharacterSelection {
         {
             match self.current_menu {
                 CurrentSelection::Difficulty => {
-                    if self.difficulty_selected == 1 {
-                        self.current_menu = CurrentSelection::Character;
-                    }
+                    self.current_menu = CurrentSelection::Character;
                 }
                 CurrentSelection::Character => {
-                    if self.character_select[0] == 0 && self.character_select[1] == 0 {
-                        state.score = ScoreData::new(Difficulty::Normal);
-                        state.change_scene(Box::new(StageView::new("stg1".to_owned(), stage1())));
+                    if self.character_select[0] == 0 {
+                        let player = match self.character_select[1] {
+                            0 => PlayerKind::ReimuA,
+                            _ => PlayerKind::ReimuB,
+                        };
+                        let difficulty = if self.mode == RunMode::Extra {
+                            Difficulty::Extra
+                        } else {
+                            Difficulty::from_id(self.difficulty_selected as u8)
+                                .unwrap_or(Difficulty::Normal)
+                        };
+                        match self.mode {
+                            RunMode::Practice => state
+                                .change_scene(Box::new(PracticeSelection::new(difficulty, player))),
+                            _ => state.change_scene(Box::new(StageView::new(
+                                first_stage(difficulty),
+                                ScoreData::new(difficulty, player),
+                            ))),
+                        }
                     }
                 }
             }
@@ -1,12 +1,15 @@
-use crate::{controls::Action, ui::basic_choice::BasicChoice};
+use crate::{controls::Action, player::PlayerKind, ui::basic_choice::BasicChoice};
 
-use super::{character_selection::CharacterSelection, music_room::MusicRoom, Scene};
+use super::{
+    character_selection::CharacterSelection, music_room::MusicRoom, replay_menu::ReplayMenu,
+    spell_practice::SpellPractice, Scene,
+};
 use raylib::prelude::*;
 
 #[derive(Debug)]
 pub struct MainMenu {
     current_index: usize,
-    choices: [BasicChoice; 8],
+    choices: [BasicChoice; 9],
 }
 
 impl MainMenu {
@@ -17,10 +20,11 @@ impl MainMenu {
             choices: [
                 BasicChoice::new("Start", false),
                 BasicChoice::new("Extra Start", true),
+                BasicChoice::new("Practice Start", false),
+                BasicChoice::new("Spell Practice", true),
                 BasicChoice::new("Player Data", true),
-                BasicChoice::new("Replay", true),
+                BasicChoice::new("Replay", false),
                 BasicChoice::new("Music Room", false),
-                BasicChoice::new("Replay", true),
                 BasicChoice::new("Option", true),
                 BasicChoice::new("Exit", false),
             ],
@@ -30,6 +34,8 @@ impl MainMenu {
 
 impl Scene for MainMenu {
     fn init(&mut self, state: &mut crate::state::State) {
+        self.choices[1].disabled = !state.save.extra_unlocked;
+        self.choices[3].disabled = state.spell_history.iter().next().is_none();
         state.audio.play_bgm(0, state.bgm_volume);
     }
 
@@ -118,14 +124,23 @@ impl Scene for MainMenu {
         if state.controls.is_pressed(Action::Accept, d)
             || state.controls.is_pressed(Action::Attack, d)
         {
-            if self.current_index < 8 {
+            if self.current_index < 9 {
                 state.audio.select_sfx.play(state.sfx_volume);
             }
             match self.current_index {
                 0 => state.change_scene(Box::new(CharacterSelection::new())),
-                4 => state.change_scene(Box::new(MusicRoom::new(&state))),
-
-                7 => state.should_quit = true,
+                1 if !self.choices[1].disabled => {
+                    state.change_scene(Box::new(CharacterSelection::extra()))
+                }
+                2 => state.change_scene(Box::new(CharacterSelection::practice())),
+                3 if !self.choices[3].disabled => {
+                    let player = state.save.last_player.unwrap_or(PlayerKind::ReimuA);
+                    state.change_scene(Box::new(SpellPractice::new(player, 0)))
+                }
+                5 => state.change_scene(Box::new(ReplayMenu::new())),
+                6 => state.change_scene(Box::new(MusicRoom::new(&state))),
+
+                8 => state.should_quit = true,
                 _ => {}
             }
         }
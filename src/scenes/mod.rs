@@ -8,6 +8,9 @@ pub mod character_selection;
 pub mod instruction;
 pub mod main_menu;
 pub mod music_room;
+pub mod practice_selection;
+pub mod replay_menu;
+pub mod spell_practice;
 pub mod stage_view;
 
 pub trait Scene: Debug {
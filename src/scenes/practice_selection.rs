@@ -0,0 +1,165 @@
+use raylib::prelude::*;
+
+use crate::{
+    controls::Action,
+    difficulty::Difficulty,
+    player::PlayerKind,
+    score::{ScoreData, StartConfig, MAX_POWER},
+    stage::EXTRA_STAGE,
+    state::State,
+    ui::basic_choice::BasicChoice,
+};
+
+use super::{character_selection::CharacterSelection, stage_view::StageView, Scene};
+
+// INFO : Stage practice, only the stages already reached with this difficulty and character
+// can be picked
+#[derive(Debug)]
+pub struct PracticeSelection {
+    difficulty: Difficulty,
+    player: PlayerKind,
+    power: f32,
+    selection_index: usize,
+    choices: Vec<BasicChoice>,
+}
+
+impl PracticeSelection {
+    pub fn new(difficulty: Difficulty, player: PlayerKind) -> Self {
+        Self {
+            difficulty,
+            player,
+            power: MAX_POWER,
+            selection_index: 0,
+            choices: (0..EXTRA_STAGE)
+                .map(|stage| BasicChoice::new(&format!("Stage {}", stage + 1), true))
+                .collect(),
+        }
+    }
+}
+
+impl Scene for PracticeSelection {
+    fn init(&mut self, state: &mut State) {
+        let reached = state.save.stages_reached(self.difficulty, self.player);
+        for (i, choice) in self.choices.iter_mut().enumerate() {
+            choice.disabled = i >= reached;
+        }
+
+        if state.audio.current_played_bgm != Some(0) {
+            state.audio.play_bgm(0, state.bgm_volume);
+        }
+    }
+
+    fn clean_up(&mut self, _: &mut State) {
+        //
+    }
+
+    fn update(&mut self, d: &mut RaylibDrawHandle, state: &mut State) {
+        state.audio.update_bgm();
+
+        if state.controls.is_pressed(Action::Escape, d) {
+            state.audio.select_sfx.play(state.sfx_volume);
+            state.change_scene(Box::new(CharacterSelection::practice()));
+            return;
+        }
+
+        if state.controls.is_pressed(Action::Down, d) {
+            self.selection_index = (self.selection_index + 1) % self.choices.len();
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if state.controls.is_pressed(Action::Up, d) {
+            if self.selection_index == 0 {
+                self.selection_index = self.choices.len();
+            }
+            self.selection_index -= 1;
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if state.controls.is_pressed(Action::Left, d) {
+            self.power = (self.power - 1.).max(0.);
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if state.controls.is_pressed(Action::Right, d) {
+            self.power = (self.power + 1.).min(MAX_POWER);
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if state.controls.is_pressed(Action::Accept, d)
+            || state.controls.is_pressed(Action::Attack, d)
+        {
+            if self.choices[self.selection_index].disabled {
+                return;
+            }
+
+            state.audio.select_sfx.play(state.sfx_volume);
+            state.audio.stop_bgm();
+            let start = StartConfig::practice(self.difficulty, self.power);
+            state.change_scene(Box::new(StageView::new(
+                self.selection_index,
+                ScoreData::with_start(self.difficulty, self.player, start),
+            )));
+        }
+    }
+
+    fn draw(
+        &mut self,
+        d: &mut RaylibBlendMode<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        state: &State,
+    ) {
+        d.draw_texture(state.assets.get("main_menu"), 0, 0, Color::WHITE);
+        d.draw_text_pro(
+            &state.assets.font,
+            "Practice Start",
+            Vector2::new(200., 20.),
+            Vector2::new(0., 0.),
+            0.,
+            42.,
+            0.,
+            Color::WHITE,
+        );
+        d.draw_text_pro(
+            &state.assets.font,
+            self.difficulty.as_ref(),
+            Vector2::new(40., 80.),
+            Vector2::new(0., 0.),
+            0.,
+            21.,
+            0.,
+            Color::GRAY,
+        );
+
+        let position = Vector2::new(60., 120.);
+        let font_size = 21.;
+        for (i, val) in self.choices.iter().enumerate() {
+            let position = Vector2::new(position.x, position.y + font_size * i as f32);
+            val.draw(d, i == self.selection_index, position, font_size, state);
+
+            let high_score = state
+                .save
+                .practice_high_score(self.difficulty, self.player, i)
+                .unwrap_or(0);
+            d.draw_text_pro(
+                &state.assets.font,
+                &format!("{:08}", high_score),
+                Vector2::new(position.x + 160., position.y),
+                Vector2::new(0., 0.),
+                0.,
+                font_size,
+                0.5,
+                Color::GRAY,
+            );
+        }
+
+        d.draw_text_pro(
+            &state.assets.font,
+            &format!("< Power {:.2} >", self.power),
+            Vector2::new(60., 400.),
+            Vector2::new(0., 0.),
+            0.,
+            font_size,
+            0.,
+            Color::WHITE,
+        );
+    }
+}
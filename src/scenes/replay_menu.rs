@@ -0,0 +1,134 @@
+use std::path::PathBuf;
+
+use raylib::prelude::*;
+
+use crate::{controls::Action, replay::Replay, state::State, ui::basic_choice::BasicChoice};
+
+use super::{main_menu::MainMenu, stage_view::StageView, Scene};
+
+#[derive(Debug)]
+pub struct ReplayMenu {
+    pub selection_index: usize,
+    pub replays: Vec<PathBuf>,
+    pub choices: Vec<BasicChoice>,
+}
+
+impl ReplayMenu {
+    pub fn new() -> Self {
+        let replays = Replay::list();
+        let choices = replays
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_stem()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                BasicChoice::new(&name, false)
+            })
+            .collect();
+
+        Self {
+            selection_index: 0,
+            replays,
+            choices,
+        }
+    }
+}
+
+impl Scene for ReplayMenu {
+    fn init(&mut self, state: &mut State) {
+        if state.audio.current_played_bgm != Some(0) {
+            state.audio.play_bgm(0, state.bgm_volume);
+        }
+    }
+
+    fn clean_up(&mut self, _: &mut State) {
+        //
+    }
+
+    fn update(&mut self, d: &mut RaylibDrawHandle, state: &mut State) {
+        state.audio.update_bgm();
+
+        if state.controls.is_pressed(Action::Escape, d) {
+            state.audio.select_sfx.play(state.sfx_volume);
+            state.change_scene(Box::new(MainMenu::new()));
+            return;
+        }
+
+        if self.choices.is_empty() {
+            return;
+        }
+
+        if state.controls.is_pressed(Action::Down, d) {
+            self.selection_index = (self.selection_index + 1) % self.choices.len();
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if state.controls.is_pressed(Action::Up, d) {
+            if self.selection_index == 0 {
+                self.selection_index = self.choices.len();
+            }
+            self.selection_index -= 1;
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if state.controls.is_pressed(Action::Accept, d)
+            || state.controls.is_pressed(Action::Attack, d)
+        {
+            if self.choices[self.selection_index].disabled {
+                return;
+            }
+
+            state.audio.select_sfx.play(state.sfx_volume);
+            match Replay::load(&self.replays[self.selection_index]) {
+                Ok(replay) => {
+                    state.audio.stop_bgm();
+                    state.change_scene(Box::new(StageView::from_replay(replay)));
+                }
+                Err(err) => {
+                    eprintln!("[-] Failed to load replay : {}", err);
+                    self.choices[self.selection_index].disabled = true;
+                }
+            }
+        }
+    }
+
+    fn draw(
+        &mut self,
+        d: &mut RaylibBlendMode<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        state: &State,
+    ) {
+        d.draw_texture(state.assets.get("main_menu"), 0, 0, Color::WHITE);
+        d.draw_text_pro(
+            &state.assets.font,
+            "Replay",
+            Vector2::new(250., 20.),
+            Vector2::new(0., 0.),
+            0.,
+            42.,
+            0.,
+            Color::WHITE,
+        );
+
+        if self.choices.is_empty() {
+            d.draw_text_pro(
+                &state.assets.font,
+                "No replay saved yet",
+                Vector2::new(40., 80.),
+                Vector2::new(0., 0.),
+                0.,
+                21.,
+                0.,
+                Color::GRAY,
+            );
+            return;
+        }
+
+        let position = Vector2::new(40., 80.);
+        let font_size = 21.;
+        for (i, val) in self.choices.iter().enumerate() {
+            let position = Vector2::new(position.x, position.y + font_size * i as f32);
+            val.draw(d, i == self.selection_index, position, font_size, state);
+        }
+    }
+}
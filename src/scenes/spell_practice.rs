@@ -0,0 +1,282 @@
+use raylib::prelude::*;
+
+use crate::{
+    controls::Action, difficulty::Difficulty, player::PlayerKind, stage::script::BossKind,
+    state::State,
+};
+
+use super::{main_menu::MainMenu, stage_view::StageView, Scene};
+
+const DIFFICULTIES: [Difficulty; 5] = [
+    Difficulty::Easy,
+    Difficulty::Normal,
+    Difficulty::Hard,
+    Difficulty::Lunatic,
+    Difficulty::Extra,
+];
+const VISIBLE_ROWS: usize = 16;
+
+#[derive(Debug)]
+struct SpellEntry {
+    boss: BossKind,
+    difficulty: Difficulty,
+    name: String,
+    attempts: u32,
+    captures: u32,
+}
+
+#[derive(Debug)]
+enum Row {
+    Group(String),
+    Spell(usize),
+}
+
+// INFO : Every spell card already declared in front of the chosen character, grouped by boss
+// and difficulty, in the order they show up in the fight
+#[derive(Debug)]
+pub struct SpellPractice {
+    player: PlayerKind,
+    selection_index: usize,
+    entries: Vec<SpellEntry>,
+    rows: Vec<Row>,
+}
+
+impl SpellPractice {
+    pub fn new(player: PlayerKind, selection_index: usize) -> Self {
+        Self {
+            player,
+            selection_index,
+            entries: Vec::new(),
+            rows: Vec::new(),
+        }
+    }
+
+    fn load_entries(&mut self, state: &State) {
+        self.entries.clear();
+        self.rows.clear();
+
+        for boss in BossKind::ALL {
+            // INFO : A broken pattern only hide its boss, the other ones can still be practiced
+            let pattern = match boss.pattern() {
+                Ok(pattern) => pattern,
+                Err(err) => {
+                    eprintln!("[-] Failed to load {} pattern : {}", boss.name(), err);
+                    continue;
+                }
+            };
+            for difficulty in DIFFICULTIES {
+                let mut group = true;
+                for name in pattern.spell_names() {
+                    let Some(record) = state.spell_history.get(name, difficulty, self.player)
+                    else {
+                        continue;
+                    };
+                    if group {
+                        self.rows.push(Row::Group(format!(
+                            "{} - {}",
+                            boss.name(),
+                            difficulty.as_ref()
+                        )));
+                        group = false;
+                    }
+                    self.rows.push(Row::Spell(self.entries.len()));
+                    self.entries.push(SpellEntry {
+                        boss,
+                        difficulty,
+                        name: name.to_owned(),
+                        attempts: record.attempts,
+                        captures: record.captures,
+                    });
+                }
+            }
+        }
+
+        self.selection_index = self
+            .selection_index
+            .min(self.entries.len().saturating_sub(1));
+    }
+}
+
+impl Scene for SpellPractice {
+    fn init(&mut self, state: &mut State) {
+        self.load_entries(state);
+
+        if state.audio.current_played_bgm != Some(0) {
+            state.audio.play_bgm(0, state.bgm_volume);
+        }
+    }
+
+    fn clean_up(&mut self, _: &mut State) {
+        //
+    }
+
+    fn update(&mut self, d: &mut RaylibDrawHandle, state: &mut State) {
+        state.audio.update_bgm();
+
+        if state.controls.is_pressed(Action::Escape, d) {
+            state.audio.select_sfx.play(state.sfx_volume);
+            state.change_scene(Box::new(MainMenu::new()));
+            return;
+        }
+
+        if state.controls.is_pressed(Action::Left, d) || state.controls.is_pressed(Action::Right, d)
+        {
+            self.player = match self.player {
+                PlayerKind::ReimuA => PlayerKind::ReimuB,
+                PlayerKind::ReimuB => PlayerKind::ReimuA,
+            };
+            self.selection_index = 0;
+            self.load_entries(state);
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if self.entries.is_empty() {
+            return;
+        }
+
+        if state.controls.is_pressed(Action::Down, d) {
+            self.selection_index = (self.selection_index + 1) % self.entries.len();
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if state.controls.is_pressed(Action::Up, d) {
+            if self.selection_index == 0 {
+                self.selection_index = self.entries.len();
+            }
+            self.selection_index -= 1;
+            state.audio.select_sfx.play(state.sfx_volume);
+        }
+
+        if state.controls.is_pressed(Action::Accept, d)
+            || state.controls.is_pressed(Action::Attack, d)
+        {
+            let entry = &self.entries[self.selection_index];
+            match StageView::spell_practice(
+                entry.boss,
+                &entry.name,
+                entry.difficulty,
+                self.player,
+                self.selection_index,
+            ) {
+                Ok(view) => {
+                    state.audio.select_sfx.play(state.sfx_volume);
+                    state.audio.stop_bgm();
+                    state.change_scene(Box::new(view));
+                }
+                Err(err) => eprintln!("[-] Failed to start the spell practice : {}", err),
+            }
+        }
+    }
+
+    fn draw(
+        &mut self,
+        d: &mut RaylibBlendMode<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+        state: &State,
+    ) {
+        d.draw_texture(state.assets.get("main_menu"), 0, 0, Color::WHITE);
+        d.draw_text_pro(
+            &state.assets.font,
+            "Spell Practice",
+            Vector2::new(200., 20.),
+            Vector2::new(0., 0.),
+            0.,
+            42.,
+            0.,
+            Color::WHITE,
+        );
+        d.draw_text_pro(
+            &state.assets.font,
+            &format!("< {} >", self.player.as_ref()),
+            Vector2::new(480., 40.),
+            Vector2::new(0., 0.),
+            0.,
+            18.,
+            0.,
+            Color::WHITE,
+        );
+
+        if self.entries.is_empty() {
+            d.draw_text_pro(
+                &state.assets.font,
+                "No spell card encountered yet",
+                Vector2::new(40., 80.),
+                Vector2::new(0., 0.),
+                0.,
+                21.,
+                0.,
+                Color::GRAY,
+            );
+            return;
+        }
+
+        // INFO : Scroll so the selected spell is always on screen
+        let selected_row = self
+            .rows
+            .iter()
+            .position(|row| matches!(row, Row::Spell(i) if *i == self.selection_index))
+            .unwrap_or(0);
+        let first_row = (selected_row + 1).saturating_sub(VISIBLE_ROWS);
+
+        let font_size = 18.;
+        for (i, row) in self
+            .rows
+            .iter()
+            .skip(first_row)
+            .take(VISIBLE_ROWS)
+            .enumerate()
+        {
+            let y = 80. + (font_size + 6.) * i as f32;
+            match row {
+                Row::Group(title) => {
+                    d.draw_text_pro(
+                        &state.assets.font,
+                        title,
+                        Vector2::new(20., y),
+                        Vector2::new(0., 0.),
+                        0.,
+                        font_size,
+                        0.,
+                        Color::GOLD,
+                    );
+                }
+                Row::Spell(index) => {
+                    let entry = &self.entries[*index];
+                    let active = *index == self.selection_index;
+                    let color = if active { Color::WHITE } else { Color::GRAY };
+                    if active {
+                        d.draw_text_pro(
+                            &state.assets.font,
+                            ">",
+                            Vector2::new(24., y),
+                            Vector2::new(0., 0.),
+                            0.,
+                            font_size,
+                            0.,
+                            Color::WHITE,
+                        );
+                    }
+                    d.draw_text_pro(
+                        &state.assets.font,
+                        &entry.name,
+                        Vector2::new(40., y),
+                        Vector2::new(0., 0.),
+                        0.,
+                        font_size,
+                        0.,
+                        color,
+                    );
+                    d.draw_text_pro(
+                        &state.assets.font,
+                        &format!("{:02}/{:02}", entry.captures, entry.attempts),
+                        Vector2::new(560., y),
+                        Vector2::new(0., 0.),
+                        0.,
+                        font_size,
+                        0.5,
+                        color,
+                    );
+                }
+            }
+        }
+    }
+}
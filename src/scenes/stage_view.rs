@@ -1,46 +1,81 @@
-use std::fmt::Debug;
+use std::{fmt::Debug, io};
 
-use hecs::World;
 use raylib::prelude::*;
 
 use crate::{
-    controls::Action,
+    controls::{Action, ActionState},
     difficulty::Difficulty,
-    event::EventManager,
-    score::ScoreData,
-    stage::stage1,
+    player::PlayerKind,
+    replay::{Replay, ReplayPlayer, RANK_SAMPLE_TICKS},
+    save::SAVE_PATH,
+    score::{
+        ScoreData, StageResult, StartConfig, BOMB_PIECES_PER_SPELL, LIFE_PIECES_PER_LIFE, MAX_POWER,
+    },
+    simulation::{AudioCue, Simulation, SpellCardEvent, CUTIN_DURATION},
+    spell_history::SPELL_HISTORY_PATH,
+    stage::{
+        first_stage, is_last_stage,
+        script::{BossKind, StageScript},
+        stage,
+    },
     systems::{
-        delete_offscreen, draw_boss_bg, draw_boss_hp, draw_circle_hitbox, draw_focus,
-        draw_sprites_system, invulnerable_delay_update, player_control, rotate_focus,
-        update_boss_attack, update_collision, update_movement, wanderable_search,
+        draw_boss_bg, draw_boss_hp, draw_circle_hitbox, draw_focus, draw_lasers,
+        draw_sprites_system,
+    },
+    ui::basic_choice::BasicChoice,
+    utility::{
+        clock::{FixedClock, TICK_DELTA},
+        get_sprite_coord,
+        random::Rng,
     },
-    ui::{basic_choice::BasicChoice, dialog::Dialog},
-    utility::get_sprite_coord,
 };
 
-use super::{main_menu::MainMenu, Scene};
+use super::{
+    main_menu::MainMenu, practice_selection::PracticeSelection, replay_menu::ReplayMenu,
+    spell_practice::SpellPractice, Scene,
+};
 
 #[derive(Debug, PartialEq)]
 enum GameState {
     Paused,
-    Dialog,
     Resumed,
+    GameOver,
+    StageClear,
+    // INFO : Whether the card was captured
+    SpellPracticeEnd(bool),
+}
+
+// INFO : A single spell card from the spell practice, the run end with the card
+#[derive(Debug)]
+struct SpellPracticeRun {
+    // INFO : Where to put the cursor back in the spell list
+    selection_index: usize,
+    declared: bool,
 }
 
 pub struct StageView {
-    pub world: World,
+    pub sim: Simulation,
+    // INFO : Index in stage::STAGES and the score it started with, for the result tally
+    stage: usize,
+    start: ScoreData,
+    result: Option<StageResult>,
     pub camera: Camera2D,
     bg: String,
 
     pub bg_pos: Vector2,
+    prev_bg_pos: Vector2,
     pub bg_movement: Vector2,
     state: GameState,
 
     current_index: usize,
     choices: [BasicChoice; 3],
 
-    event: Option<EventManager>,
-    pub dialog: Option<Dialog>,
+    clock: FixedClock,
+    input: ActionState,
+    pending_input: ActionState,
+    recording: Option<Replay>,
+    playback: Option<ReplayPlayer>,
+    spell_practice: Option<SpellPracticeRun>,
 }
 
 impl Debug for StageView {
@@ -50,8 +85,47 @@ impl Debug for StageView {
 }
 
 impl StageView {
-    pub fn new(bg: String, event: EventManager) -> Self {
-        let world = World::new();
+    pub fn new(index: usize, score: ScoreData) -> Self {
+        Self::with_script(index, stage(index), score)
+    }
+
+    fn with_script(index: usize, script: StageScript, score: ScoreData) -> Self {
+        let seed = Rng::from_time().seed();
+        let sim = Simulation::new(script.events(), score.clone(), seed);
+        let mut view = Self::with_simulation(index, script.bg, sim);
+        view.recording = Some(Replay::new(seed, index as u8, score));
+        view
+    }
+
+    pub fn from_replay(replay: Replay) -> Self {
+        let index = replay.stage as usize;
+        let script = stage(index);
+        let sim = Simulation::new(script.events(), replay.start.clone(), replay.seed);
+        let mut view = Self::with_simulation(index, script.bg, sim);
+        view.playback = Some(ReplayPlayer::new(replay));
+        view
+    }
+
+    pub fn spell_practice(
+        boss: BossKind,
+        spell: &str,
+        difficulty: Difficulty,
+        player: PlayerKind,
+        selection_index: usize,
+    ) -> io::Result<Self> {
+        let script = StageScript::spell_practice(boss, spell)?;
+        let start = StartConfig::practice(difficulty, MAX_POWER);
+        let score = ScoreData::with_start(difficulty, player, start);
+        let sim = Simulation::new(script.events(), score, Rng::from_time().seed());
+        let mut view = Self::with_simulation(boss.stage(), script.bg, sim);
+        view.spell_practice = Some(SpellPracticeRun {
+            selection_index,
+            declared: false,
+        });
+        Ok(view)
+    }
+
+    fn with_simulation(stage: usize, bg: String, sim: Simulation) -> Self {
         let camera = Camera2D {
             target: Vector2 { x: 0.0, y: 0.0 },
             offset: Vector2 { x: 0., y: 0. },
@@ -62,14 +136,23 @@ impl StageView {
         let bg_movement = Vector2::new(0., 100.);
         let state = GameState::Resumed;
         Self {
-            world,
+            start: sim.score.clone(),
+            sim,
+            stage,
+            result: None,
             bg,
             camera,
             bg_pos,
+            prev_bg_pos: bg_pos,
             bg_movement,
             state,
-            event: Some(event),
-            dialog: None,
+
+            clock: FixedClock::new(),
+            input: ActionState::default(),
+            pending_input: ActionState::default(),
+            recording: None,
+            playback: None,
+            spell_practice: None,
 
             current_index: 0,
             choices: [
@@ -80,15 +163,211 @@ impl StageView {
         }
     }
 
-    pub fn push_dialog(&mut self, dialog: Dialog) {
-        self.dialog = Some(dialog);
-        self.state = GameState::Dialog;
+    pub fn is_replay(&self) -> bool {
+        self.playback.is_some()
+    }
+
+    fn save_replay(&mut self) {
+        if let Some(replay) = self.recording.take() {
+            if replay.frames.is_empty() {
+                return;
+            }
+            if let Err(err) = replay.save_new() {
+                eprintln!("[-] Failed to save replay : {}", err);
+            }
+        }
+    }
+
+    // INFO : Input for the current tick either from the keyboard (recorded) or from the replay
+    fn next_input(&mut self) -> Option<ActionState> {
+        match &mut self.playback {
+            Some(player) => player.next_frame(),
+            None => {
+                let input = self.pending_input;
+                // INFO : A press is only consumed once even if this frame run multiple tick
+                self.pending_input.pressed = 0;
+                if let Some(replay) = &mut self.recording {
+                    replay.push(input);
+                }
+                Some(input)
+            }
+        }
+    }
+
+    fn step(&mut self, input: &ActionState) {
+        self.prev_bg_pos = self.bg_pos;
+        if self.sim.dialog.is_none() {
+            self.bg_pos += self.bg_movement * TICK_DELTA;
+            if self.bg_pos.y >= 448. {
+                self.bg_pos.y = 0.;
+            }
+        }
+
+        self.sim.step(input);
+        // INFO : u64::is_multiple_of need rust 1.87
+        #[allow(clippy::manual_is_multiple_of)]
+        if self.sim.ticks % RANK_SAMPLE_TICKS == 0 {
+            if let Some(replay) = &mut self.recording {
+                replay.rank.push(self.sim.score.rank.value);
+            }
+        }
+
+        if let Some(captured) = self.spell_practice_result() {
+            self.state = GameState::SpellPracticeEnd(captured);
+        } else if self.sim.is_game_over() {
+            self.game_over();
+        } else if self.sim.is_stage_clear() {
+            self.stage_clear();
+        }
+    }
+
+    // INFO : The spell practice is over as soon as the card is failed or once it ended
+    fn spell_practice_result(&mut self) -> Option<bool> {
+        let practice = self.spell_practice.as_mut()?;
+        match &self.sim.spell_card {
+            Some(card) if card.failed => Some(false),
+            Some(_) => {
+                practice.declared = true;
+                None
+            }
+            None if practice.declared => Some(
+                self.sim
+                    .spell_events
+                    .iter()
+                    .any(|event| matches!(event, SpellCardEvent::Captured(_))),
+            ),
+            None => None,
+        }
+    }
+
+    fn back_to_spell_practice(&self, state: &mut crate::state::State) {
+        if let Some(practice) = &self.spell_practice {
+            state.change_scene(Box::new(SpellPractice::new(
+                self.sim.score.player,
+                practice.selection_index,
+            )));
+        }
+    }
+
+    fn game_over(&mut self) {
+        self.state = GameState::GameOver;
+        self.save_replay();
+
+        // INFO : A continued run can't be replayed, the replay is already saved up to here
+        let can_continue = !self.is_replay() && self.sim.score.credits() > 0;
+        self.choices[0].disabled = !can_continue;
+        self.current_index = if can_continue { 0 } else { 1 };
+    }
+
+    fn stage_clear(&mut self) {
+        let result = StageResult::new(self.stage, &self.start, &self.sim.score);
+        self.sim.score.score += result.total();
+        self.result = Some(result);
+        self.state = GameState::StageClear;
+        self.save_replay();
+    }
+
+    fn next_stage(&mut self, state: &mut crate::state::State) {
+        if self.is_replay() {
+            state.change_scene(Box::new(ReplayMenu::new()));
+        } else if self.sim.score.practice {
+            self.record_score(state);
+            state.change_scene(Box::new(PracticeSelection::new(
+                self.sim.score.difficulty,
+                self.sim.score.player,
+            )));
+        } else if !is_last_stage(self.stage) {
+            state.change_scene(Box::new(self.following_stage(stage(self.stage + 1))));
+        } else {
+            // INFO : Clearing the main game on Normal or above open the extra stage
+            let difficulty = self.sim.score.difficulty;
+            if difficulty != Difficulty::Extra && difficulty.id() >= Difficulty::Normal.id() {
+                state.save.extra_unlocked = true;
+            }
+            self.record_score(state);
+            // TODO : Ending
+            state.change_scene(Box::new(MainMenu::new()));
+        }
+    }
+
+    // INFO : The run go on with everything the player had at the end of this stage, the
+    // clear bonus included
+    fn following_stage(&self, script: StageScript) -> Self {
+        Self::with_script(self.stage + 1, script, self.sim.score.clone())
+    }
+
+    // INFO : Called once the run is over, cleared or not
+    fn record_score(&self, state: &mut crate::state::State) {
+        if self.is_replay() || self.spell_practice.is_some() {
+            return;
+        }
+        let score = &self.sim.score;
+        if score.practice {
+            state.save.record_practice_score(
+                score.difficulty,
+                score.player,
+                self.stage,
+                score.score,
+            );
+        } else {
+            state
+                .save
+                .record_score(score.difficulty, score.player, score.score);
+        }
+        if let Err(err) = state.save.save(SAVE_PATH) {
+            eprintln!("[-] Failed to save save data : {}", err);
+        }
+    }
+
+    fn play_audio(&mut self, state: &mut crate::state::State) {
+        for cue in self.sim.audio.drain(..) {
+            match cue {
+                AudioCue::Sfx(sfx) => state.audio.play_sfx(sfx, state.sfx_volume),
+                AudioCue::Bgm(id) => state.audio.play_bgm(id, state.bgm_volume),
+                AudioCue::StopBgm => state.audio.stop_bgm(),
+            }
+        }
+    }
+
+    // INFO : Replays don't count toward the spell history, they were already recorded once
+    fn record_spells(&mut self, state: &mut crate::state::State) {
+        if self.sim.spell_events.is_empty() {
+            return;
+        }
+        let events = std::mem::take(&mut self.sim.spell_events);
+        if self.is_replay() {
+            return;
+        }
+
+        let difficulty = self.sim.score.difficulty;
+        let player = self.sim.score.player;
+        for event in events {
+            match event {
+                SpellCardEvent::Declared(name) => {
+                    state.spell_history.attempt(&name, difficulty, player)
+                }
+                SpellCardEvent::Captured(name) => {
+                    state.spell_history.capture(&name, difficulty, player)
+                }
+            }
+        }
+        if let Err(err) = state.spell_history.save(SPELL_HISTORY_PATH) {
+            eprintln!("[-] Failed to save spell history : {}", err);
+        }
+    }
+
+    fn alpha(&self, state: &crate::state::State) -> f32 {
+        if state.interpolation {
+            self.clock.alpha()
+        } else {
+            1.
+        }
     }
 }
 
 impl Scene for StageView {
     fn clean_up(&mut self, _: &mut crate::state::State) {
-        //
+        self.save_replay();
     }
 
     fn update(
@@ -96,16 +375,42 @@ impl Scene for StageView {
         d: &mut raylib::prelude::RaylibDrawHandle,
         state: &mut crate::state::State,
     ) {
-        if state.controls.is_pressed(Action::Escape, d) {
+        if state.controls.is_pressed(Action::Debug, d) {
+            state.debug = !state.debug;
+        }
+
+        if state.controls.is_pressed(Action::Escape, d) && self.spell_practice.is_some() {
+            state.audio.select_sfx.play(state.sfx_volume);
+            self.back_to_spell_practice(state);
+            return;
+        }
+
+        if state.controls.is_pressed(Action::Escape, d) && self.sim.dialog.is_none() {
             match self.state {
                 GameState::Paused => self.state = GameState::Resumed,
                 GameState::Resumed => self.state = GameState::Paused,
-                GameState::Dialog => {}
+                _ => {}
             }
         }
 
         match self.state {
-            GameState::Paused => {
+            GameState::SpellPracticeEnd(_) => {
+                if state.controls.is_pressed(Action::Accept, d)
+                    || state.controls.is_pressed(Action::Attack, d)
+                {
+                    state.audio.select_sfx.play(state.sfx_volume);
+                    self.back_to_spell_practice(state);
+                }
+            }
+            GameState::StageClear => {
+                if state.controls.is_pressed(Action::Accept, d)
+                    || state.controls.is_pressed(Action::Attack, d)
+                {
+                    state.audio.select_sfx.play(state.sfx_volume);
+                    self.next_stage(state);
+                }
+            }
+            GameState::Paused | GameState::GameOver => {
                 if state.controls.is_pressed(Action::Down, d) {
                     self.current_index = (self.current_index + 1) % self.choices.len() as usize;
                     state.audio.select_sfx.play(state.sfx_volume);
@@ -126,12 +431,29 @@ impl Scene for StageView {
                         state.audio.select_sfx.play(state.sfx_volume);
                     }
                     match self.current_index {
-                        0 if state.score.life > 0 => self.state = GameState::Resumed,
-                        1 => {
-                            state.score = ScoreData::new(Difficulty::Normal);
-                            state
-                                .change_scene(Box::new(StageView::new("stg1".to_owned(), stage1())))
+                        0 if self.choices[0].disabled => {}
+                        0 if self.state == GameState::GameOver => {
+                            self.sim.score.use_continue();
+                            self.state = GameState::Resumed;
                         }
+                        0 => self.state = GameState::Resumed,
+                        1 => match &self.playback {
+                            Some(player) => state.change_scene(Box::new(StageView::from_replay(
+                                player.replay.clone(),
+                            ))),
+                            // INFO : A practice restart the same stage with the same setup
+                            None if self.start.practice => state.change_scene(Box::new(
+                                StageView::new(self.stage, self.start.clone()),
+                            )),
+                            None => {
+                                let difficulty = self.sim.score.difficulty;
+                                state.change_scene(Box::new(StageView::new(
+                                    first_stage(difficulty),
+                                    ScoreData::new(difficulty, self.sim.score.player),
+                                )))
+                            }
+                        },
+                        2 if self.is_replay() => state.change_scene(Box::new(ReplayMenu::new())),
                         2 => state.change_scene(Box::new(MainMenu::new())),
 
                         _ => {}
@@ -140,38 +462,28 @@ impl Scene for StageView {
             }
             GameState::Resumed => {
                 state.audio.update_bgm();
-                self.bg_pos += self.bg_movement * d.get_frame_time();
-                if self.bg_pos.y >= 448. {
-                    self.bg_pos.y = 0.;
-                }
 
-                let mut event = self.event.take().unwrap();
-                event.update(self, state, d.get_frame_time());
-                self.event = Some(event);
-
-                player_control(&mut self.world, state, d);
-                update_movement(&self.world, d);
-                rotate_focus(&self.world, d);
-                delete_offscreen(&mut self.world);
-                wanderable_search(&self.world, d);
-                update_boss_attack(&mut self.world, state, d);
-                update_collision(&mut self.world, state);
-                invulnerable_delay_update(&mut self.world, d);
-
-                if state.score.life < 0 {
-                    self.state = GameState::Paused
-                }
-            }
-            GameState::Dialog => {
-                state.audio.update_bgm();
-                if state.controls.is_pressed(Action::Attack, d) {
-                    let mut di = self.dialog.take().unwrap();
-                    di.next();
-                    if di.done() {
-                        self.state = GameState::Resumed;
+                let live = state.controls.snapshot(d);
+                self.pending_input.down = live.down;
+                self.pending_input.pressed |= live.pressed;
+
+                self.clock.accumulate(d.get_frame_time());
+                while self.clock.tick() {
+                    let Some(input) = self.next_input() else {
+                        state.change_scene(Box::new(ReplayMenu::new()));
                         return;
+                    };
+                    self.input = input;
+                    self.step(&input);
+
+                    if self.state != GameState::Resumed {
+                        break;
                     }
-                    self.dialog = Some(di);
+                }
+                self.play_audio(state);
+                self.record_spells(state);
+                if self.state == GameState::GameOver {
+                    self.record_score(state);
                 }
             }
         }
@@ -185,7 +497,7 @@ impl Scene for StageView {
         d.draw_texture(&state.assets.get("stage_view"), 0, 0, Color::WHITE);
         d.draw_text_pro(
             &state.assets.font,
-            state.score.difficulty.as_ref(),
+            self.sim.score.difficulty.as_ref(),
             Vector2::new(480., 20.),
             Vector2::new(0., 0.),
             0.,
@@ -212,7 +524,16 @@ impl Scene for StageView {
             0.,
             Color::WHITE,
         );
-        let score_text = format!("{:08}", 00100000);
+        let score = &self.sim.score;
+        let high_score = if score.practice {
+            state
+                .save
+                .practice_high_score(score.difficulty, score.player, self.stage)
+        } else {
+            state.save.high_score(score.difficulty, score.player)
+        };
+        let high_score = high_score.unwrap_or(0).max(score.score);
+        let score_text = format!("{:08}", high_score);
         d.draw_text_pro(
             &state.assets.font,
             &score_text,
@@ -234,7 +555,7 @@ impl Scene for StageView {
             0.,
             Color::WHITE,
         );
-        let score_text = format!("{:08}", state.score.score);
+        let score_text = format!("{:08}", self.sim.score.score);
 
         d.draw_text_pro(
             &state.assets.font,
@@ -260,7 +581,7 @@ impl Scene for StageView {
         );
 
         for i in 0..5 {
-            let coord = if state.score.life > i {
+            let coord = if self.sim.score.life > i {
                 get_sprite_coord(7, 0, 32., 32.)
             } else {
                 get_sprite_coord(7, 1, 32., 32.)
@@ -275,6 +596,17 @@ impl Scene for StageView {
                 Color::WHITE,
             );
         }
+        let pieces = format!("{}/{}", self.sim.score.life_pieces, LIFE_PIECES_PER_LIFE);
+        d.draw_text_pro(
+            &state.assets.font,
+            &pieces,
+            Vector2::new(640., 104.),
+            Vector2::new(0., 0.),
+            0.,
+            18.,
+            0.,
+            Color::WHITE,
+        );
 
         // INFO : Spell Cards
         d.draw_text_pro(
@@ -289,7 +621,7 @@ impl Scene for StageView {
         );
 
         for i in 0..5 {
-            let coord = if state.score.spell > i {
+            let coord = if self.sim.score.spell > i {
                 get_sprite_coord(4, 0, 32., 32.)
             } else {
                 get_sprite_coord(4, 1, 32., 32.)
@@ -304,6 +636,17 @@ impl Scene for StageView {
                 Color::WHITE,
             );
         }
+        let pieces = format!("{}/{}", self.sim.score.bomb_pieces, BOMB_PIECES_PER_SPELL);
+        d.draw_text_pro(
+            &state.assets.font,
+            &pieces,
+            Vector2::new(640., 128.),
+            Vector2::new(0., 0.),
+            0.,
+            18.,
+            0.,
+            Color::WHITE,
+        );
 
         // INFO : Power
         d.draw_text_pro(
@@ -325,7 +668,7 @@ impl Scene for StageView {
             0.,
             Color::WHITE,
         );
-        let value = format!("{:.2}", state.score.power);
+        let value = format!("{:.2}", self.sim.score.power);
         d.draw_text_pro(
             &state.assets.font,
             &value,
@@ -357,7 +700,7 @@ impl Scene for StageView {
             Color::WHITE,
         );
 
-        let score_text = format!("{:08}", state.score.value);
+        let score_text = format!("{:08}", self.sim.score.value);
         d.draw_text_pro(
             &state.assets.font,
             &score_text,
@@ -380,7 +723,7 @@ impl Scene for StageView {
             0.,
             Color::WHITE,
         );
-        let score_text = format!("{:08}", state.score.graze);
+        let score_text = format!("{:08}", self.sim.score.graze);
         d.draw_text_pro(
             &state.assets.font,
             &score_text,
@@ -392,13 +735,48 @@ impl Scene for StageView {
             Color::WHITE,
         );
 
+        if state.debug {
+            let modifier = self.sim.score.rank.modifier(self.sim.score.difficulty);
+            d.draw_text_pro(
+                &state.assets.font,
+                &format!(
+                    "Rank {:.3} (x{:.2})",
+                    self.sim.score.rank.value, modifier.speed
+                ),
+                Vector2::new(420., 444.),
+                Vector2::new(0., 0.),
+                0.,
+                16.,
+                0.,
+                Color::YELLOW,
+            );
+        }
+
+        if self.is_replay() {
+            d.draw_text_pro(
+                &state.assets.font,
+                "Replay",
+                Vector2::new(420., 224.),
+                Vector2::new(0., 0.),
+                0.,
+                18.,
+                0.,
+                Color::WHITE,
+            );
+        }
+
         match self.state {
-            GameState::Paused => {
+            GameState::Paused | GameState::GameOver => {
                 let position = Vector2::new(70., 250.);
                 let font_size = 21.;
+                let title = if self.state == GameState::GameOver {
+                    "Game Over"
+                } else {
+                    "Game Paused"
+                };
                 d.draw_text_pro(
                     &state.assets.font,
-                    "Game Paused",
+                    title,
                     Vector2::new(position.x, position.y - 32.),
                     Vector2::new(0., 0.),
                     0.,
@@ -406,17 +784,90 @@ impl Scene for StageView {
                     0.,
                     Color::WHITE,
                 );
+                if self.state == GameState::GameOver {
+                    d.draw_text_pro(
+                        &state.assets.font,
+                        &format!("Credits {}", self.sim.score.credits()),
+                        Vector2::new(position.x, position.y + font_size * 4.),
+                        Vector2::new(0., 0.),
+                        0.,
+                        18.,
+                        0.,
+                        Color::WHITE,
+                    );
+                }
                 for (i, val) in self.choices.iter().enumerate() {
                     let position = Vector2::new(position.x, position.y + font_size * i as f32);
                     val.draw(d, i == self.current_index, position, font_size, state);
                 }
             }
-            GameState::Resumed => {}
-            GameState::Dialog => {
-                d.draw_rectangle(0, 0, 640, 480, Color::new(0, 0, 0, 128));
-                let di = self.dialog.take().unwrap();
-                di.draw(state, d);
-                self.dialog = Some(di);
+            GameState::SpellPracticeEnd(captured) => {
+                let title = if captured {
+                    "Spell Card Captured"
+                } else {
+                    "Spell Card Failed"
+                };
+                d.draw_text_pro(
+                    &state.assets.font,
+                    title,
+                    Vector2::new(70., 218.),
+                    Vector2::new(0., 0.),
+                    0.,
+                    28.,
+                    0.,
+                    Color::WHITE,
+                );
+            }
+            GameState::StageClear => {
+                let Some(result) = &self.result else {
+                    return;
+                };
+                let lines = [
+                    ("Clear", format!("{}", result.clear)),
+                    ("Graze", format!("{}", result.graze)),
+                    ("Point Items", format!("{}", result.point_items)),
+                    ("Difficulty", format!("x{:.1}", result.multiplier)),
+                    ("Total", format!("{}", result.total())),
+                ];
+                d.draw_text_pro(
+                    &state.assets.font,
+                    &result.title(),
+                    Vector2::new(70., 150.),
+                    Vector2::new(0., 0.),
+                    0.,
+                    28.,
+                    0.,
+                    Color::WHITE,
+                );
+                for (i, (name, value)) in lines.iter().enumerate() {
+                    let y = 200. + 24. * i as f32;
+                    d.draw_text_pro(
+                        &state.assets.font,
+                        name,
+                        Vector2::new(70., y),
+                        Vector2::new(0., 0.),
+                        0.,
+                        18.,
+                        0.,
+                        Color::WHITE,
+                    );
+                    d.draw_text_pro(
+                        &state.assets.font,
+                        value,
+                        Vector2::new(230., y),
+                        Vector2::new(0., 0.),
+                        0.,
+                        18.,
+                        0.5,
+                        Color::WHITE,
+                    );
+                }
+            }
+            GameState::Resumed => {
+                if let Some(di) = &self.sim.dialog {
+                    d.draw_rectangle(0, 0, 640, 480, Color::new(0, 0, 0, 128));
+                    di.draw(state, d);
+                }
             }
         }
     }
@@ -426,22 +877,78 @@ impl Scene for StageView {
         d: &mut RaylibTextureMode<'_, RaylibDrawHandle<'_>>,
         state: &crate::state::State,
     ) {
+        let alpha = self.alpha(state);
         let mut md = d.begin_mode2D(self.camera);
 
+        // INFO : Don't interpolate when the background just wrapped around
+        let bg_y = if self.prev_bg_pos.y <= self.bg_pos.y {
+            self.prev_bg_pos.y + (self.bg_pos.y - self.prev_bg_pos.y) * alpha
+        } else {
+            self.bg_pos.y
+        };
         for i in 0..2 {
             md.draw_texture_v(
                 &state.assets.get(&self.bg),
-                Vector2::new(0., self.bg_pos.y - 448. * i as f32),
+                Vector2::new(0., bg_y - 448. * i as f32),
                 Color::WHITE,
             );
         }
 
         {
-            draw_boss_bg(&self.world, state, &mut md);
-            draw_sprites_system(&self.world, state, &mut md);
-            draw_focus(&self.world, state, &mut md);
-            // draw_circle_hitbox(&self.world, &mut md);
-            draw_boss_hp(&self.world, state, &mut md);
+            draw_boss_bg(&self.sim.world, state, alpha, &mut md);
+            draw_lasers(&self.sim.world, state, alpha, &mut md);
+            draw_sprites_system(&self.sim.world, state, alpha, &mut md);
+            draw_focus(&self.sim.world, state, &self.input, alpha, &mut md);
+            // draw_circle_hitbox(&self.sim.world, &mut md);
+            draw_boss_hp(&self.sim.world, state, alpha, &mut md);
+            if let Some(cutin) = &self.sim.cutin {
+                // INFO : Portrait slide in fast, drift a bit then fade out
+                let t = 1. - cutin.lifetime / CUTIN_DURATION;
+                let x = 384. - 264. * (t * 4.).min(1.) - 40. * t;
+                let fade = ((1. - t) * 4.).min(1.);
+                let color = Color::new(255, 255, 255, (fade * 200.) as u8);
+                md.draw_texture_ex(
+                    state.assets.get(&cutin.portrait),
+                    Vector2::new(x, 40.),
+                    0.,
+                    0.8,
+                    color,
+                );
+                md.draw_text_pro(
+                    &state.assets.font,
+                    &cutin.name,
+                    Vector2::new(20., 400. - 60. * (t * 3.).min(1.)),
+                    Vector2::new(0., 0.),
+                    0.,
+                    18.,
+                    0.,
+                    color,
+                );
+            }
+            if let Some(banner) = &self.sim.banner {
+                md.draw_text_pro(
+                    &state.assets.font,
+                    &banner.title,
+                    Vector2::new(70., 120.),
+                    Vector2::new(0., 0.),
+                    0.,
+                    24.,
+                    0.,
+                    Color::WHITE,
+                );
+                if let Some(bonus) = banner.bonus {
+                    md.draw_text_pro(
+                        &state.assets.font,
+                        &format!("{:08}", bonus),
+                        Vector2::new(140., 150.),
+                        Vector2::new(0., 0.),
+                        0.,
+                        20.,
+                        0.5,
+                        Color::WHITE,
+                    );
+                }
+            }
             // self.world.query::<&Wanderable>().iter().for_each(|(_, w)| {
             //     if let Some(tgt) = w.target_pos {
             //         md.draw_rectangle(tgt.re as i32 - 32, tgt.im as i32 - 32, 32, 32, Color::RED);
@@ -461,12 +968,74 @@ impl Scene for StageView {
             //     });
         }
 
-        if self.state == GameState::Paused {
+        if self.state != GameState::Resumed {
             md.draw_rectangle(0, 0, 384, 448, Color::new(0, 0, 0, 128));
         }
     }
 
-    fn init(&mut self, _: &mut crate::state::State) {
-        //
+    // INFO : Every stage started in a regular run open in the stage practice
+    fn init(&mut self, state: &mut crate::state::State) {
+        if self.is_replay() || self.sim.score.practice {
+            return;
+        }
+        let score = &self.sim.score;
+        state
+            .save
+            .reach_stage(score.difficulty, score.player, self.stage);
+        state.save.last_player = Some(score.player);
+        if let Err(err) = state.save.save(SAVE_PATH) {
+            eprintln!("[-] Failed to save save data : {}", err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NEXT_STAGE: &str = r#"
+bg = "stg1"
+
+[[events]]
+at = 0.0
+action = "spawn_player"
+"#;
+
+    #[test]
+    fn next_stage_carry_the_run_over() {
+        let mut view = StageView::new(0, ScoreData::new(Difficulty::Normal, PlayerKind::ReimuA));
+        let score = &mut view.sim.score;
+        score.score = 123_450;
+        score.graze = 40;
+        score.point_items = 12;
+        score.life = 1;
+        score.spell = 2;
+        score.power = 2.5;
+        score.continues = 1;
+        view.stage_clear();
+        let cleared = view.sim.score.clone();
+        assert!(cleared.score > 123_450);
+
+        let script = StageScript::parse(NEXT_STAGE).unwrap();
+        let mut next = view.following_stage(script);
+        assert_eq!(next.stage, 1);
+        for score in [&next.sim.score, &next.start] {
+            assert_eq!(score.score, cleared.score);
+            assert_eq!(score.graze, 40);
+            assert_eq!(score.life, 1);
+            assert_eq!(score.spell, 2);
+            assert_eq!(score.power, 2.5);
+            assert_eq!(score.continues, 1);
+            assert_eq!(score.player, PlayerKind::ReimuA);
+        }
+        let replay = next.recording.as_ref().unwrap();
+        assert_eq!(replay.stage, 1);
+        assert_eq!(replay.start.score, cleared.score);
+
+        // INFO : The tally of the next stage only count what happen in it
+        next.step(&ActionState::default());
+        let result = StageResult::new(next.stage, &next.start, &next.sim.score);
+        assert_eq!(result.graze, 0);
+        assert_eq!(result.point_items, 0);
     }
 }
@@ -1,6 +1,24 @@
-use crate::difficulty::{self, Difficulty};
+use crate::{
+    difficulty::{self, Difficulty},
+    player::PlayerKind,
+    rank::Rank,
+    stage::EXTRA_STAGE,
+};
 
-#[derive(Debug)]
+pub const MAX_POWER: f32 = 4.0;
+pub const MAX_LIFE: i8 = 8;
+pub const MAX_SPELL: i8 = 8;
+pub const BOMB_PIECES_PER_SPELL: u8 = 3;
+pub const LIFE_PIECES_PER_LIFE: u8 = 3;
+pub const MAX_CONTINUES: u8 = 3;
+
+const STAGE_CLEAR_BONUS: u64 = 1_000_000;
+// INFO : The extra stage is not in the stage count, it is worth more than any main stage
+const EXTRA_CLEAR_BONUS: u64 = 3_000_000;
+const GRAZE_BONUS: u64 = 1_000;
+const POINT_ITEM_BONUS: u64 = 2_000;
+
+#[derive(Debug, Clone)]
 pub struct ScoreData {
     pub graze: i32,
     pub score: u64,
@@ -8,7 +26,50 @@ pub struct ScoreData {
     pub value: u64,
     pub life: i8,
     pub spell: i8,
+    pub bomb_pieces: u8,
+    pub life_pieces: u8,
+    pub point_items: u32,
+    pub continues: u8,
+    pub rank: Rank,
     pub difficulty: Difficulty,
+    pub player: PlayerKind,
+    // INFO : Single stage run, it has its own score table and can't continue
+    pub practice: bool,
+}
+
+// INFO : What a run start with, the main game use the difficulty default and the stage
+// practice let the player pick
+#[derive(Debug, Clone, Copy)]
+pub struct StartConfig {
+    pub life: i8,
+    pub spell: i8,
+    pub power: f32,
+    pub practice: bool,
+}
+
+impl StartConfig {
+    pub fn new(difficulty: Difficulty) -> Self {
+        // INFO : Extra start at full power but with less to spare
+        let (life, spell, power) = match difficulty {
+            Difficulty::Extra => (2, 2, MAX_POWER),
+            _ => (3, 3, 1.0),
+        };
+        Self {
+            life,
+            spell,
+            power,
+            practice: false,
+        }
+    }
+
+    pub fn practice(difficulty: Difficulty, power: f32) -> Self {
+        Self {
+            life: MAX_LIFE,
+            power: power.clamp(0., MAX_POWER),
+            practice: true,
+            ..Self::new(difficulty)
+        }
+    }
 }
 
 impl Default for ScoreData {
@@ -20,21 +81,142 @@ impl Default for ScoreData {
             value: 10000,
             life: 3,
             spell: 3,
+            bomb_pieces: 0,
+            life_pieces: 0,
+            point_items: 0,
+            continues: 0,
+            rank: Rank::default(),
             difficulty: Difficulty::Normal,
+            player: PlayerKind::ReimuA,
+            practice: false,
         }
     }
 }
 
 impl ScoreData {
-    pub fn new(difficulty: Difficulty) -> Self {
+    pub fn new(difficulty: Difficulty, player: PlayerKind) -> Self {
+        Self::with_start(difficulty, player, StartConfig::new(difficulty))
+    }
+
+    pub fn with_start(difficulty: Difficulty, player: PlayerKind, start: StartConfig) -> Self {
         Self {
             graze: 0,
             score: 0,
-            power: 1.0,
+            power: start.power,
             value: 10000,
-            life: 3,
-            spell: 3,
+            life: start.life,
+            spell: start.spell,
+            bomb_pieces: 0,
+            life_pieces: 0,
+            point_items: 0,
+            continues: 0,
+            rank: Rank::default(),
             difficulty,
+            player,
+            practice: start.practice,
         }
     }
+
+    pub fn add_power(&mut self, amount: f32) {
+        self.power = (self.power + amount).min(MAX_POWER);
+    }
+
+    pub fn add_bomb_piece(&mut self) {
+        self.bomb_pieces += 1;
+        if self.bomb_pieces >= BOMB_PIECES_PER_SPELL {
+            self.bomb_pieces = 0;
+            self.spell = (self.spell + 1).min(MAX_SPELL);
+        }
+    }
+
+    // INFO : No continue in Extra nor in practice
+    pub fn credits(&self) -> u8 {
+        if self.difficulty == Difficulty::Extra || self.practice {
+            return 0;
+        }
+        MAX_CONTINUES.saturating_sub(self.continues)
+    }
+
+    // INFO : Back to full life and spell, the score restart from the number of continue used
+    pub fn use_continue(&mut self) {
+        let fresh = Self::new(self.difficulty, self.player);
+        self.continues += 1;
+        self.score = self.continues as u64;
+        self.life = fresh.life;
+        self.spell = fresh.spell;
+        self.bomb_pieces = 0;
+        self.life_pieces = 0;
+    }
+
+    pub fn add_life_piece(&mut self) {
+        self.life_pieces += 1;
+        if self.life_pieces >= LIFE_PIECES_PER_LIFE {
+            self.life_pieces = 0;
+            self.life = (self.life + 1).min(MAX_LIFE);
+        }
+    }
+}
+
+// INFO : Tally shown once a stage is cleared, only what happened during that stage count
+#[derive(Debug, Clone, Copy)]
+pub struct StageResult {
+    pub stage: usize,
+    pub extra: bool,
+    pub clear: u64,
+    pub graze: u64,
+    pub point_items: u64,
+    pub multiplier: f32,
+}
+
+impl StageResult {
+    // INFO : index is the one in stage::STAGES
+    pub fn new(index: usize, start: &ScoreData, end: &ScoreData) -> Self {
+        let graze = end.graze.saturating_sub(start.graze).max(0) as u64;
+        let point_items = end.point_items.saturating_sub(start.point_items) as u64;
+        let extra = index == EXTRA_STAGE;
+        let clear = if extra {
+            EXTRA_CLEAR_BONUS
+        } else {
+            STAGE_CLEAR_BONUS * (index + 1) as u64
+        };
+        Self {
+            stage: index + 1,
+            extra,
+            clear,
+            graze: graze * GRAZE_BONUS,
+            point_items: point_items * POINT_ITEM_BONUS,
+            multiplier: end.difficulty.score_multiplier(),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        if self.extra {
+            "Extra Clear".to_owned()
+        } else {
+            format!("Stage {} Clear", self.stage)
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        let total = ((self.clear + self.graze + self.point_items) as f32 * self.multiplier) as u64;
+        (total / 10) * 10
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extra_clear_use_its_own_bonus() {
+        let start = ScoreData::new(Difficulty::Normal, PlayerKind::ReimuA);
+        let stage = StageResult::new(0, &start, &start);
+        assert_eq!(stage.title(), "Stage 1 Clear");
+        assert_eq!(stage.clear, STAGE_CLEAR_BONUS);
+
+        let start = ScoreData::new(Difficulty::Extra, PlayerKind::ReimuA);
+        let extra = StageResult::new(EXTRA_STAGE, &start, &start);
+        assert_eq!(extra.title(), "Extra Clear");
+        assert_eq!(extra.clear, EXTRA_CLEAR_BONUS);
+    }
 }
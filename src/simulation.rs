@@ -0,0 +1,446 @@
+use hecs::{Entity, World};
+
+use crate::{
+    assets::sfx::SoundEffect,
+    controls::{Action, ActionState},
+    event::EventManager,
+    math::SpatialGrid,
+    score::ScoreData,
+    systems::{
+        delete_offscreen, invulnerable_delay_update, player_control, rotate_focus,
+        store_previous_position, update_bombs, update_boss_attack, update_boss_defeat,
+        update_bullet_timeline, update_collision, update_dying, update_enemy_attack, update_homing,
+        update_items, update_lasers, update_movement, update_movement_path, update_particles,
+        update_phase_intro, update_player_options, update_rank, wanderable_search,
+    },
+    ui::dialog::Dialog,
+    utility::{clock::TICK_DELTA, random::Rng},
+};
+
+pub const PLAY_FIELD: (f32, f32) = (384., 448.);
+// INFO : 9 ticks, roughly what the mainline games give
+pub const DEATHBOMB_WINDOW: f32 = 0.15;
+const GRID_CELL_SIZE: f32 = 32.;
+
+// INFO : Everything the simulation want to be heard, the scene decide how to play it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioCue {
+    Sfx(SoundEffect),
+    Bgm(usize),
+    StopBgm,
+}
+
+// INFO : Spell card currently declared by a boss, it's failed as soon as the player get hit
+// or bomb during it
+#[derive(Debug, Clone)]
+pub struct SpellCard {
+    pub name: String,
+    pub failed: bool,
+}
+
+// INFO : Reported to the scene so it can keep the spell history, the simulation never touch
+// the disk itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpellCardEvent {
+    Declared(String),
+    Captured(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct Banner {
+    pub title: String,
+    pub bonus: Option<u64>,
+    pub lifetime: f32,
+}
+
+// INFO : Spell card declaration, the boss portrait slide across the field with the name
+#[derive(Debug, Clone)]
+pub struct Cutin {
+    pub name: String,
+    pub portrait: String,
+    pub lifetime: f32,
+}
+
+pub const CUTIN_DURATION: f32 = 1.5;
+
+// INFO : The gameplay of a stage without any raylib window, input come as ActionState,
+// time is always TICK_DELTA and randomness come from the seeded Rng so it can be stepped
+// headlessly (tests, replay validation) and still behave exactly the same as in game
+pub struct Simulation {
+    pub world: World,
+    pub score: ScoreData,
+    pub rng: Rng,
+    pub dialog: Option<Dialog>,
+    pub ticks: u64,
+    pub audio: Vec<AudioCue>,
+    // INFO : Broad phase of update_collision, rebuilt from scratch every tick
+    pub bullet_grid: SpatialGrid<Entity>,
+    pub enemy_grid: SpatialGrid<Entity>,
+    // INFO : Time the player has to bomb after being hit, 0 disable deathbombing
+    pub deathbomb_window: f32,
+    pub spell_card: Option<SpellCard>,
+    pub spell_events: Vec<SpellCardEvent>,
+    pub banner: Option<Banner>,
+    pub cutin: Option<Cutin>,
+    // INFO : The stage timeline wait while a boss is fighting, see EventManager::update
+    pub boss_fight: bool,
+    pub stage_clear: bool,
+
+    event: Option<EventManager>,
+}
+
+impl Simulation {
+    pub fn new(event: EventManager, score: ScoreData, seed: u64) -> Self {
+        Self {
+            world: World::new(),
+            score,
+            rng: Rng::new(seed),
+            dialog: None,
+            ticks: 0,
+            audio: Vec::new(),
+            bullet_grid: SpatialGrid::new(PLAY_FIELD.0, PLAY_FIELD.1, GRID_CELL_SIZE),
+            enemy_grid: SpatialGrid::new(PLAY_FIELD.0, PLAY_FIELD.1, GRID_CELL_SIZE),
+            deathbomb_window: DEATHBOMB_WINDOW,
+            spell_card: None,
+            spell_events: Vec::new(),
+            banner: None,
+            cutin: None,
+            boss_fight: false,
+            stage_clear: false,
+            event: Some(event),
+        }
+    }
+
+    pub fn step(&mut self, input: &ActionState) {
+        self.ticks += 1;
+
+        if let Some(mut dialog) = self.dialog.take() {
+            if input.is_pressed(Action::Attack) {
+                dialog.next();
+            }
+            if !dialog.done() {
+                self.dialog = Some(dialog);
+            }
+            return;
+        }
+
+        store_previous_position(&self.world);
+
+        let mut event = self.event.take().unwrap();
+        event.update(self, TICK_DELTA);
+        self.event = Some(event);
+
+        player_control(self, input, TICK_DELTA);
+        update_dying(self, TICK_DELTA);
+        update_movement_path(&mut self.world, TICK_DELTA);
+        update_homing(&mut self.world, TICK_DELTA);
+        update_movement(&self.world, TICK_DELTA);
+        update_player_options(self, input.is_down(Action::Focus));
+        update_bullet_timeline(self, TICK_DELTA);
+        update_lasers(&mut self.world, TICK_DELTA);
+        rotate_focus(&self.world, TICK_DELTA);
+        delete_offscreen(&mut self.world);
+        wanderable_search(&self.world, &mut self.rng, TICK_DELTA);
+        update_phase_intro(&mut self.world, TICK_DELTA);
+        update_boss_attack(self, TICK_DELTA);
+        update_boss_defeat(self, TICK_DELTA);
+        update_enemy_attack(self, TICK_DELTA);
+        update_collision(self);
+        update_bombs(self, TICK_DELTA);
+        update_items(self, input.is_down(Action::Focus));
+        invulnerable_delay_update(&mut self.world, TICK_DELTA);
+        update_particles(&mut self.world, TICK_DELTA);
+        update_rank(self, TICK_DELTA);
+
+        if let Some(banner) = &mut self.banner {
+            banner.lifetime -= TICK_DELTA;
+        }
+        self.banner = self.banner.take().filter(|banner| banner.lifetime > 0.);
+        if let Some(cutin) = &mut self.cutin {
+            cutin.lifetime -= TICK_DELTA;
+        }
+        self.cutin = self.cutin.take().filter(|cutin| cutin.lifetime > 0.);
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.score.life < 0
+    }
+
+    pub fn is_stage_clear(&self) -> bool {
+        self.stage_clear
+    }
+
+    pub fn push_dialog(&mut self, dialog: Dialog) {
+        self.dialog = Some(dialog);
+    }
+
+    pub fn declare_spell_card(&mut self, name: &str) {
+        self.spell_card = Some(SpellCard {
+            name: name.to_owned(),
+            failed: false,
+        });
+        self.spell_events
+            .push(SpellCardEvent::Declared(name.to_owned()));
+    }
+
+    pub fn fail_spell_card(&mut self) {
+        if let Some(card) = &mut self.spell_card {
+            card.failed = true;
+        }
+    }
+
+    pub fn play_sfx(&mut self, sfx: SoundEffect) {
+        let cue = AudioCue::Sfx(sfx);
+        if !self.audio.contains(&cue) {
+            self.audio.push(cue);
+        }
+    }
+
+    pub fn play_bgm(&mut self, id: usize) {
+        self.audio.push(AudioCue::Bgm(id));
+    }
+
+    pub fn stop_bgm(&mut self) {
+        self.audio.push(AudioCue::StopBgm);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_complex::Complex;
+
+    use super::*;
+    use crate::{
+        cmpx,
+        components::{
+            Bullet, CircleHitbox, Controllable, Enemy, MoveParams, Player, Sprite, Transform2D,
+        },
+        difficulty::Difficulty,
+        entity::{create_enemy_bullet, miko, reimu_a},
+        pattern::BossPattern,
+        player::PlayerKind,
+        replay::{Replay, ReplayPlayer},
+        stage::stage,
+        vec2,
+    };
+
+    const SEED: u64 = 0x5eed;
+
+    fn input(actions: &[Action]) -> ActionState {
+        let down = actions
+            .iter()
+            .fold(0, |bits, action| bits | 1 << *action as u16);
+        ActionState::new(down, down)
+    }
+
+    fn stage1(seed: u64) -> Simulation {
+        let score = ScoreData::new(Difficulty::Normal, PlayerKind::ReimuA);
+        Simulation::new(stage(0).events(), score, seed)
+    }
+
+    fn seconds(seconds: f32) -> usize {
+        (seconds / TICK_DELTA) as usize
+    }
+
+    fn count_enemies(sim: &Simulation) -> usize {
+        sim.world
+            .query::<&Enemy>()
+            .without::<&Bullet>()
+            .iter()
+            .count()
+    }
+
+    fn count_enemy_bullets(sim: &Simulation) -> usize {
+        sim.world.query::<(&Enemy, &Bullet)>().iter().count()
+    }
+
+    fn player_position(sim: &Simulation) -> Option<Complex<f32>> {
+        sim.world
+            .query::<(&Player, &Controllable, &Transform2D)>()
+            .iter()
+            .map(|(_, (_, _, transform))| transform.position)
+            .next()
+    }
+
+    #[test]
+    fn stage_spawn_scripted_enemies_and_bullets() {
+        let mut sim = stage1(SEED);
+        sim.step(&ActionState::default());
+        assert!(player_position(&sim).is_some());
+        assert_eq!(count_enemies(&sim), 0);
+
+        let mut enemies = 0;
+        let mut bullets = 0;
+        for _ in 0..seconds(5.) {
+            sim.step(&ActionState::default());
+            enemies = enemies.max(count_enemies(&sim));
+            bullets = bullets.max(count_enemy_bullets(&sim));
+        }
+        // INFO : The first wave is 5 fairies that shoot at the player once they stopped
+        assert_eq!(enemies, 5);
+        assert!(bullets > 0);
+    }
+
+    #[test]
+    fn shooting_fairies_raise_the_score() {
+        let mut sim = stage1(SEED);
+        let attack = input(&[Action::Attack, Action::Focus]);
+        let mut left = input(&[Action::Attack, Action::Focus, Action::Left]);
+        left.pressed = 0;
+        for tick in 0..seconds(8.) {
+            // INFO : Slide under the first wave
+            let input = if (60..100).contains(&tick) {
+                left
+            } else {
+                attack
+            };
+            sim.step(&input);
+        }
+        assert!(sim.score.score > 0);
+    }
+
+    #[test]
+    fn enemy_bullet_on_the_player_take_a_life() {
+        let mut sim = stage1(SEED);
+        sim.step(&ActionState::default());
+        let life = sim.score.life;
+        let position = player_position(&sim).unwrap();
+
+        create_enemy_bullet(
+            &mut sim.world,
+            Transform2D::new(position, vec2!(1.), 0.),
+            Sprite::new("miko_sprite", 0, 3, 32., 32.),
+            MoveParams::move_linear(cmpx!(0.)),
+            CircleHitbox::new(4., vec2!(0.)),
+        );
+        for _ in 0..seconds(sim.deathbomb_window + 0.1) {
+            sim.step(&ActionState::default());
+        }
+        assert_eq!(sim.score.life, life - 1);
+    }
+
+    #[test]
+    fn player_top_speed_match_the_120_fps_tuning() {
+        let mut sim = stage1(SEED);
+        sim.step(&ActionState::default());
+        let mut right = input(&[Action::Right]);
+        right.pressed = 0;
+        for _ in 0..30 {
+            sim.step(&right);
+        }
+
+        let before = player_position(&sim).unwrap();
+        sim.step(&right);
+        let speed = (player_position(&sim).unwrap() - before).re / TICK_DELTA;
+        // INFO : 5000 px/s² with 0.85 retention per frame at 120 FPS
+        let expected = 5000. / 120. / 0.15;
+        assert!((speed - expected).abs() < 1., "{} px/s", speed);
+    }
+
+    #[test]
+    fn replay_reproduce_the_same_run() {
+        let mut rng = Rng::new(42);
+        let actions = [
+            Action::Attack,
+            Action::Focus,
+            Action::Up,
+            Action::Down,
+            Action::Left,
+            Action::Right,
+        ];
+
+        let mut sim = stage1(SEED);
+        let mut replay = Replay::new(SEED, 0, sim.score.clone());
+        let mut held = ActionState::default();
+        for tick in 0..seconds(12.) {
+            if tick % 20 == 0 {
+                let pick = actions
+                    .iter()
+                    .filter(|_| rng.next_f32() < 0.4)
+                    .copied()
+                    .collect::<Vec<_>>();
+                held = input(&pick);
+            } else {
+                held.pressed = 0;
+            }
+            replay.push(held);
+            sim.step(&held);
+        }
+
+        let replay = Replay::from_bytes(&replay.to_bytes()).unwrap();
+        let mut replayed = Simulation::new(stage(0).events(), replay.start.clone(), replay.seed);
+        let mut player = ReplayPlayer::new(replay);
+        while let Some(input) = player.next_frame() {
+            replayed.step(&input);
+        }
+
+        assert_eq!(replayed.ticks, sim.ticks);
+        assert_eq!(replayed.score.score, sim.score.score);
+        assert_eq!(replayed.score.graze, sim.score.graze);
+        assert_eq!(replayed.score.life, sim.score.life);
+        assert_eq!(replayed.score.power, sim.score.power);
+        assert_eq!(replayed.score.rank.value, sim.score.rank.value);
+        assert_eq!(player_position(&replayed), player_position(&sim));
+        assert_eq!(count_enemies(&replayed), count_enemies(&sim));
+        assert_eq!(count_enemy_bullets(&replayed), count_enemy_bullets(&sim));
+        assert_eq!(replayed.world.len(), sim.world.len());
+    }
+
+    const SURVIVAL_CARD: &str = r#"
+[[moves]]
+type = "spell"
+name = "Test Sign 'Endure'"
+timeout = 3.0
+hp = 100000.0
+survival = true
+
+[moves.attack]
+type = "circle"
+sides = 4
+speed = 20.0
+rotation_per_fire = 0.0
+rotation = 0.0
+cooldown = 5.0
+bullet = { sheet = "miko_sprite", cell = [0, 3], hitbox = 2.5 }
+"#;
+
+    // INFO : Keep shooting at a boss that only have one spell card until it run out of time
+    fn time_out_spell_card(source: &str) -> Simulation {
+        let mut sim = Simulation::new(
+            EventManager::default(),
+            ScoreData::new(Difficulty::Normal, PlayerKind::ReimuA),
+            SEED,
+        );
+        reimu_a(&mut sim.world);
+        let pattern = BossPattern::parse(source).unwrap();
+        miko(&mut sim.world, &pattern, sim.score.difficulty);
+
+        let attack = input(&[Action::Attack]);
+        for _ in 0..seconds(8.) {
+            sim.step(&attack);
+        }
+        sim
+    }
+
+    #[test]
+    fn survived_spell_card_is_captured_on_timeout() {
+        let sim = time_out_spell_card(SURVIVAL_CARD);
+        assert_eq!(
+            sim.spell_events,
+            vec![
+                SpellCardEvent::Declared("Test Sign 'Endure'".to_owned()),
+                SpellCardEvent::Captured("Test Sign 'Endure'".to_owned()),
+            ]
+        );
+        assert!(sim.score.score > 0);
+    }
+
+    #[test]
+    fn timed_out_spell_card_is_not_captured() {
+        let sim = time_out_spell_card(&SURVIVAL_CARD.replace("survival = true", ""));
+        assert_eq!(
+            sim.spell_events,
+            vec![SpellCardEvent::Declared("Test Sign 'Endure'".to_owned())]
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{difficulty::Difficulty, player::PlayerKind};
+
+pub const SPELL_HISTORY_PATH: &str = "./spell_history.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpellRecord {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub player: PlayerKind,
+    pub attempts: u32,
+    pub captures: u32,
+}
+
+// INFO : How many time every spell card was declared and captured, one record per spell
+// difficulty and character so the spell practice can show them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpellHistory {
+    #[serde(default)]
+    spells: Vec<SpellRecord>,
+}
+
+impl SpellHistory {
+    pub fn parse(source: &str) -> io::Result<Self> {
+        toml::from_str(source).map_err(|err| invalid_data(err.to_string()))
+    }
+
+    // INFO : No history yet is not an error, it's just an empty one
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(source) => Self::parse(&source),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let source = toml::to_string(self).map_err(|err| invalid_data(err.to_string()))?;
+        fs::write(path, source)
+    }
+
+    pub fn get(
+        &self,
+        name: &str,
+        difficulty: Difficulty,
+        player: PlayerKind,
+    ) -> Option<&SpellRecord> {
+        self.spells.iter().find(|record| {
+            record.name == name && record.difficulty == difficulty && record.player == player
+        })
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SpellRecord> {
+        self.spells.iter()
+    }
+
+    pub fn attempt(&mut self, name: &str, difficulty: Difficulty, player: PlayerKind) {
+        self.record(name, difficulty, player).attempts += 1;
+    }
+
+    pub fn capture(&mut self, name: &str, difficulty: Difficulty, player: PlayerKind) {
+        self.record(name, difficulty, player).captures += 1;
+    }
+
+    fn record(
+        &mut self,
+        name: &str,
+        difficulty: Difficulty,
+        player: PlayerKind,
+    ) -> &mut SpellRecord {
+        let index = match self.spells.iter().position(|record| {
+            record.name == name && record.difficulty == difficulty && record.player == player
+        }) {
+            Some(index) => index,
+            None => {
+                self.spells.push(SpellRecord {
+                    name: name.to_owned(),
+                    difficulty,
+                    player,
+                    attempts: 0,
+                    captures: 0,
+                });
+                self.spells.len() - 1
+            }
+        };
+        &mut self.spells[index]
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
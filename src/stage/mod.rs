@@ -1,30 +1,27 @@
-use crate::{
-    entity::{miko, reimu_a},
-    event::EventManager,
-    ui::dialog::{Dialog, DialogItem},
-};
+pub mod script;
 
-pub fn stage1() -> EventManager {
-    let mut event = EventManager::default();
+use script::StageScript;
 
-    event.add(0., |stage, state| {
-        reimu_a(&mut stage.world);
-        // state.audio.play_bgm(2, state.bgm_volume);
-    });
+use crate::difficulty::Difficulty;
 
-    event.add(1., |stage, state| {
-        miko(&mut stage.world);
-        state.audio.play_bgm(8, state.bgm_volume);
-        stage.push_dialog(Dialog::new(
-            "reimu_char",
-            "miko_char",
-            [
-                DialogItem::Player("Dialog In Progress"),
-                DialogItem::Opponent("Dialog In Progress"),
-            ]
-            .into(),
-        ));
-    });
+// INFO : Stages of a run, played in order, the extra stage is last and played on its own.
+// There is only one main stage for now so a run end on its clear, the carry over to the next
+// stage in StageView::next_stage is only reached once more main stages are added before extra
+pub const STAGES: &[&str] = &["./assets/stages/stage1.toml", "./assets/stages/extra.toml"];
+pub const EXTRA_STAGE: usize = 1;
 
-    event
+pub fn first_stage(difficulty: Difficulty) -> usize {
+    match difficulty {
+        Difficulty::Extra => EXTRA_STAGE,
+        _ => 0,
+    }
+}
+
+// INFO : Whether clearing this stage end the run
+pub fn is_last_stage(index: usize) -> bool {
+    index + 1 >= EXTRA_STAGE
+}
+
+pub fn stage(index: usize) -> StageScript {
+    StageScript::load(STAGES[index]).expect("[-] Failed to load stage script!")
 }
@@ -0,0 +1,651 @@
+use std::{fs, io, path::Path};
+
+use hecs::Entity;
+use num_complex::Complex;
+use serde::Deserialize;
+
+use crate::{
+    cmpx,
+    components::{
+        Drops, EnemyAttack, Hitpoint, ItemKind, LaserSetup, MoveParams, MovementPath, Player,
+        Transform2D,
+    },
+    entity::{create_laser, fairy, miko, spawn_player},
+    event::EventManager,
+    pattern::{validate_laser, AttackDef, BossPattern},
+    player::PlayerKind,
+    simulation::Simulation,
+    ui::dialog::{Dialog, DialogItem},
+};
+
+use super::{EXTRA_STAGE, STAGES};
+
+// INFO : A stage described as data, the time of every event is in second since the stage start
+//
+// bg = "stg1"
+//
+// [[events]]
+// at = 1.0
+// action = "spawn_enemy"
+// enemy = "fairy"
+// position = [100.0, -20.0]
+// movement = { type = "linear", velocity = [0.0, 80.0] }
+#[derive(Debug, Clone, Deserialize)]
+pub struct StageScript {
+    pub bg: String,
+    #[serde(default)]
+    pub events: Vec<StageEvent>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StageEvent {
+    pub at: f32,
+    #[serde(flatten)]
+    pub action: StageAction,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StageAction {
+    // INFO : Spawn the character chosen for the run unless the script force one
+    SpawnPlayer {
+        player: Option<PlayerKind>,
+    },
+    SpawnEnemy {
+        position: [f32; 2],
+        #[serde(flatten)]
+        spawn: EnemySpawn,
+    },
+    // INFO : count enemy placed along the formation, one every interval second
+    SpawnWave {
+        formation: Formation,
+        count: u16,
+        #[serde(default)]
+        interval: f32,
+        #[serde(flatten)]
+        spawn: EnemySpawn,
+    },
+    // INFO : spell make the boss use only this spell card, the pattern is loaded and
+    // checked with the script so a broken one never show up mid stage
+    SpawnBoss {
+        boss: BossKind,
+        #[serde(default)]
+        spell: Option<String>,
+        #[serde(skip)]
+        pattern: Option<BossPattern>,
+    },
+    SpawnLaser {
+        position: [f32; 2],
+        #[serde(default)]
+        angle: f32,
+        laser: LaserSetup,
+    },
+    Bgm {
+        id: usize,
+    },
+    Dialog {
+        player: String,
+        opponent: String,
+        lines: Vec<DialogLine>,
+    },
+    // INFO : End of the stage, the result tally is shown then the next stage start
+    StageClear,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnemyKind {
+    Fairy,
+}
+
+// INFO : Everything needed to spawn a regular enemy except where, hp and drops default to
+// the one of the enemy kind
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnemySpawn {
+    pub enemy: EnemyKind,
+    #[serde(default)]
+    pub movement: MovementScript,
+    #[serde(default)]
+    pub path: Vec<PathStep>,
+    pub hp: Option<f32>,
+    pub attack: Option<AttackDef>,
+    #[serde(default)]
+    pub attack_delay: f32,
+    pub drops: Option<Vec<ItemKind>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PathStep {
+    pub at: f32,
+    #[serde(flatten)]
+    pub movement: MovementScript,
+}
+
+// INFO : Angle are in radian
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Formation {
+    Point {
+        position: [f32; 2],
+    },
+    Line {
+        from: [f32; 2],
+        to: [f32; 2],
+    },
+    Arc {
+        center: [f32; 2],
+        radius: f32,
+        from: f32,
+        to: f32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BossKind {
+    Miko,
+    MikoExtra,
+}
+
+impl BossKind {
+    pub const ALL: [BossKind; 2] = [BossKind::Miko, BossKind::MikoExtra];
+
+    pub fn name(&self) -> &str {
+        match self {
+            BossKind::Miko => "Toyosatomimi no Miko",
+            BossKind::MikoExtra => "Toyosatomimi no Miko (Extra)",
+        }
+    }
+
+    pub fn pattern(&self) -> io::Result<BossPattern> {
+        let path = match self {
+            BossKind::Miko => "./assets/patterns/miko.toml",
+            BossKind::MikoExtra => "./assets/patterns/miko_extra.toml",
+        };
+        BossPattern::load(path)
+    }
+
+    pub fn bgm(&self) -> usize {
+        match self {
+            BossKind::Miko => 8,
+            BossKind::MikoExtra => 11,
+        }
+    }
+
+    // INFO : Index in stage::STAGES of the stage this boss close
+    pub fn stage(&self) -> usize {
+        match self {
+            BossKind::Miko => 0,
+            BossKind::MikoExtra => EXTRA_STAGE,
+        }
+    }
+
+    pub fn spawn(&self, sim: &mut Simulation, pattern: &BossPattern) {
+        match self {
+            BossKind::Miko | BossKind::MikoExtra => {
+                miko(&mut sim.world, pattern, sim.score.difficulty)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DialogLine {
+    Player(String),
+    Opponent(String),
+}
+
+// INFO : Map directly to the MoveParams constructor with the same name
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MovementScript {
+    Linear {
+        velocity: [f32; 2],
+    },
+    Accelerated {
+        velocity: [f32; 2],
+        acceleration: [f32; 2],
+    },
+    Asymptotic {
+        from: [f32; 2],
+        to: [f32; 2],
+        halflife: f32,
+    },
+    Towards {
+        velocity: [f32; 2],
+        target: [f32; 2],
+        attraction: [f32; 2],
+    },
+    Dampen {
+        velocity: [f32; 2],
+        retention: f32,
+    },
+}
+
+impl Default for MovementScript {
+    fn default() -> Self {
+        Self::Linear { velocity: [0., 0.] }
+    }
+}
+
+impl MovementScript {
+    pub fn to_move_params(&self) -> MoveParams {
+        match self {
+            MovementScript::Linear { velocity } => {
+                MoveParams::move_linear(cmpx!(velocity[0], velocity[1]))
+            }
+            MovementScript::Accelerated {
+                velocity,
+                acceleration,
+            } => MoveParams::move_accelerated(
+                cmpx!(velocity[0], velocity[1]),
+                cmpx!(acceleration[0], acceleration[1]),
+            ),
+            MovementScript::Asymptotic { from, to, halflife } => {
+                MoveParams::move_asymptotic_halflife(
+                    cmpx!(from[0], from[1]),
+                    cmpx!(to[0], to[1]),
+                    *halflife,
+                )
+            }
+            MovementScript::Towards {
+                velocity,
+                target,
+                attraction,
+            } => MoveParams::move_towards(
+                cmpx!(velocity[0], velocity[1]),
+                cmpx!(target[0], target[1]),
+                cmpx!(attraction[0], attraction[1]),
+            ),
+            MovementScript::Dampen {
+                velocity,
+                retention,
+            } => MoveParams::move_dampen(cmpx!(velocity[0], velocity[1]), *retention),
+        }
+    }
+}
+
+impl MovementScript {
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            MovementScript::Asymptotic { halflife, .. } if *halflife <= 0. => Err(format!(
+                "asymptotic movement with a halflife of {}",
+                halflife
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Formation {
+    pub fn position(&self, index: u16, count: u16) -> [f32; 2] {
+        let t = if count > 1 {
+            index as f32 / (count - 1) as f32
+        } else {
+            0.
+        };
+        match self {
+            Formation::Point { position } => *position,
+            Formation::Line { from, to } => [
+                from[0] + (to[0] - from[0]) * t,
+                from[1] + (to[1] - from[1]) * t,
+            ],
+            Formation::Arc {
+                center,
+                radius,
+                from,
+                to,
+            } => {
+                let angle = from + (to - from) * t;
+                [
+                    center[0] + radius * angle.cos(),
+                    center[1] + radius * angle.sin(),
+                ]
+            }
+        }
+    }
+}
+
+impl EnemySpawn {
+    pub fn spawn(&self, sim: &mut Simulation, position: Complex<f32>) -> Entity {
+        let id = match self.enemy {
+            EnemyKind::Fairy => fairy(&mut sim.world, position, self.movement.to_move_params()),
+        };
+
+        if let Some(hp) = self.hp {
+            let _ = sim.world.insert_one(id, Hitpoint::new(hp));
+        }
+        if let Some(drops) = &self.drops {
+            let _ = sim.world.insert_one(id, Drops(drops.clone()));
+        }
+        if let Some(attack) = &self.attack {
+            let _ = sim.world.insert_one(
+                id,
+                EnemyAttack::new(
+                    self.attack_delay,
+                    attack.scaled(sim.score.difficulty).to_attack_move(),
+                ),
+            );
+        }
+        if !self.path.is_empty() {
+            let steps = self
+                .path
+                .iter()
+                .map(|step| (step.at, step.movement.to_move_params()))
+                .collect();
+            let _ = sim.world.insert_one(id, MovementPath::new(steps));
+        }
+        id
+    }
+
+    fn validate(&self, event: usize) -> io::Result<()> {
+        let fail = |message: String| invalid_data(format!("event {} : {}", event, message));
+
+        self.movement.validate().map_err(fail)?;
+        for (i, step) in self.path.iter().enumerate() {
+            if !step.at.is_finite() || step.at < 0. {
+                return Err(fail(format!(
+                    "path[{}] start at {} which is not a valid time",
+                    i, step.at
+                )));
+            }
+            step.movement
+                .validate()
+                .map_err(|err| fail(format!("path[{}] : {}", i, err)))?;
+        }
+
+        if let Some(hp) = self.hp {
+            if !(hp > 0. && hp.is_finite()) {
+                return Err(fail(format!("hp must be above 0, got {}", hp)));
+            }
+        }
+
+        if !self.attack_delay.is_finite() || self.attack_delay < 0. {
+            return Err(fail(format!(
+                "attack_delay can't be negative, got {}",
+                self.attack_delay
+            )));
+        }
+
+        if let Some(attack) = &self.attack {
+            let mut errors = Vec::new();
+            attack.validate("attack", &mut errors);
+            if !errors.is_empty() {
+                return Err(fail(errors.join("\n")));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StageAction {
+    // INFO : When every part of the action happen, a wave is split into one spawn per enemy
+    pub fn schedule(&self, at: f32) -> Vec<(f32, StageAction)> {
+        match self {
+            StageAction::SpawnWave {
+                formation,
+                count,
+                interval,
+                spawn,
+            } => (0..*count)
+                .map(|i| {
+                    (
+                        at + *interval * i as f32,
+                        StageAction::SpawnEnemy {
+                            position: formation.position(i, *count),
+                            spawn: spawn.clone(),
+                        },
+                    )
+                })
+                .collect(),
+            action => vec![(at, action.clone())],
+        }
+    }
+
+    pub fn apply(self, sim: &mut Simulation) {
+        match self {
+            StageAction::SpawnPlayer { player } => {
+                let player = player.unwrap_or(sim.score.player);
+                spawn_player(&mut sim.world, player);
+            }
+            StageAction::SpawnEnemy { position, spawn } => {
+                spawn.spawn(sim, cmpx!(position[0], position[1]));
+            }
+            StageAction::SpawnWave { .. } => self
+                .schedule(0.)
+                .into_iter()
+                .for_each(|(_, action)| action.apply(sim)),
+            StageAction::SpawnBoss { boss, pattern, .. } => match pattern {
+                Some(pattern) => {
+                    boss.spawn(sim, &pattern);
+                    sim.boss_fight = true;
+                }
+                None => eprintln!("[-] {} spawned without a pattern", boss.name()),
+            },
+            StageAction::SpawnLaser {
+                position,
+                angle,
+                laser,
+            } => {
+                let position = cmpx!(position[0], position[1]);
+                let target = sim
+                    .world
+                    .query::<(&Player, &Transform2D)>()
+                    .iter()
+                    .map(|(_, (_, transform))| transform.position)
+                    .next()
+                    .unwrap_or(position);
+                create_laser(&mut sim.world, position, angle, laser, target);
+            }
+            StageAction::Bgm { id } => sim.play_bgm(id),
+            StageAction::Dialog {
+                player,
+                opponent,
+                lines,
+            } => sim.push_dialog(Dialog::new(
+                &player,
+                &opponent,
+                lines
+                    .into_iter()
+                    .map(|line| match line {
+                        DialogLine::Player(text) => DialogItem::Player(text),
+                        DialogLine::Opponent(text) => DialogItem::Opponent(text),
+                    })
+                    .collect(),
+            )),
+            StageAction::StageClear => sim.stage_clear = true,
+        }
+    }
+}
+
+impl StageScript {
+    pub fn parse(source: &str) -> io::Result<Self> {
+        let mut script: StageScript =
+            toml::from_str(source).map_err(|err| invalid_data(err.to_string()))?;
+        script.load_patterns()?;
+        script.validate()?;
+        Ok(script)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path)?;
+        Self::parse(&source).map_err(|err| invalid_data(format!("{} : {}", path.display(), err)))
+    }
+
+    // INFO : Every boss pattern the script use, reduced to the spell card when one is asked
+    fn load_patterns(&mut self) -> io::Result<()> {
+        for (i, event) in self.events.iter_mut().enumerate() {
+            let StageAction::SpawnBoss {
+                boss,
+                spell,
+                pattern,
+            } = &mut event.action
+            else {
+                continue;
+            };
+
+            let loaded = boss
+                .pattern()
+                .map_err(|err| invalid_data(format!("event {} : {}", i, err)))?;
+            let loaded = match spell {
+                Some(spell) => loaded.only_spell(spell).ok_or_else(|| {
+                    invalid_data(format!(
+                        "event {} : {} has no spell card named {}",
+                        i,
+                        boss.name(),
+                        spell
+                    ))
+                })?,
+                None => loaded,
+            };
+            *pattern = Some(loaded);
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> io::Result<()> {
+        for (i, event) in self.events.iter().enumerate() {
+            if !event.at.is_finite() || event.at < 0. {
+                return Err(invalid_data(format!(
+                    "event {} start at {} which is not a valid time",
+                    i, event.at
+                )));
+            }
+
+            if let StageAction::SpawnLaser { laser, .. } = &event.action {
+                let mut errors = Vec::new();
+                validate_laser(&format!("event {}.laser", i), laser, &mut errors);
+                if !errors.is_empty() {
+                    return Err(invalid_data(errors.join("\n")));
+                }
+            }
+
+            match &event.action {
+                StageAction::SpawnEnemy { spawn, .. } => spawn.validate(i)?,
+                StageAction::SpawnWave {
+                    count,
+                    interval,
+                    spawn,
+                    ..
+                } => {
+                    if *count == 0 {
+                        return Err(invalid_data(format!("event {} spawn an empty wave", i)));
+                    }
+                    if !interval.is_finite() || *interval < 0. {
+                        return Err(invalid_data(format!(
+                            "event {} has a wave interval of {}",
+                            i, interval
+                        )));
+                    }
+                    spawn.validate(i)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    // INFO : Straight to the boss with a single spell card in front of its stage background,
+    // for the spell practice
+    pub fn spell_practice(boss: BossKind, spell: &str) -> io::Result<Self> {
+        let bg = StageScript::load(STAGES[boss.stage()])?.bg;
+        let events = vec![
+            StageAction::SpawnPlayer { player: None },
+            StageAction::Bgm { id: boss.bgm() },
+            StageAction::SpawnBoss {
+                boss,
+                spell: Some(spell.to_owned()),
+                pattern: None,
+            },
+        ];
+        let mut script = Self {
+            bg,
+            events: events
+                .into_iter()
+                .map(|action| StageEvent { at: 0., action })
+                .collect(),
+        };
+        script.load_patterns()?;
+        Ok(script)
+    }
+
+    pub fn events(&self) -> EventManager {
+        let mut event = EventManager::default();
+        for stage_event in &self.events {
+            for (at, action) in stage_event.action.schedule(stage_event.at) {
+                event.add(at, move |sim| action.apply(sim));
+            }
+        }
+        event
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOSS_ONLY: &str = r#"
+bg = "stg1"
+
+[[events]]
+at = 0.0
+action = "spawn_boss"
+boss = "miko"
+"#;
+
+    #[test]
+    fn boss_pattern_is_loaded_with_the_script() {
+        let script = StageScript::parse(BOSS_ONLY).unwrap();
+        assert!(matches!(
+            script.events[0].action,
+            StageAction::SpawnBoss {
+                pattern: Some(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn unknown_spell_card_is_rejected() {
+        let source = format!("{}spell = \"Unknown Sign 'Nothing'\"\n", BOSS_ONLY);
+        assert!(StageScript::parse(&source).is_err());
+        assert!(StageScript::spell_practice(BossKind::Miko, "Unknown Sign 'Nothing'").is_err());
+    }
+
+    #[test]
+    fn unknown_boss_is_rejected() {
+        let source = BOSS_ONLY.replace("\"miko\"", "\"marisa\"");
+        assert!(StageScript::parse(&source).is_err());
+    }
+
+    #[test]
+    fn spell_practice_keep_only_the_chosen_card() {
+        let spell = "Hermit Sign 'Taoist of the Land of the Rising Sun'";
+        let script = StageScript::spell_practice(BossKind::Miko, spell).unwrap();
+        let Some(StageAction::SpawnBoss {
+            pattern: Some(pattern),
+            ..
+        }) = script.events.iter().map(|event| &event.action).last()
+        else {
+            panic!("no boss in the spell practice script");
+        };
+        assert_eq!(pattern.spell_names().collect::<Vec<_>>(), vec![spell]);
+    }
+
+    #[test]
+    fn spell_practice_use_the_boss_stage_background() {
+        let pattern = BossKind::MikoExtra.pattern().unwrap();
+        let spell = pattern.spell_names().next().unwrap();
+        let script = StageScript::spell_practice(BossKind::MikoExtra, spell).unwrap();
+        assert_eq!(
+            script.bg,
+            StageScript::load(STAGES[EXTRA_STAGE]).unwrap().bg
+        );
+    }
+}
@@ -3,8 +3,9 @@ use raylib::{prelude::*, RaylibHandle, RaylibThread};
 use crate::{
     assets::{Assets, AudioAssets},
     controls::{init_controls, Controls},
+    save::{SaveData, SAVE_PATH},
     scenes::Scene,
-    score::ScoreData,
+    spell_history::{SpellHistory, SPELL_HISTORY_PATH},
     window::{Resolution, WindowMode},
 };
 
@@ -18,12 +19,16 @@ pub struct State<'a> {
 
     pub bgm_volume: f32,
     pub sfx_volume: f32,
+    pub interpolation: bool,
+    // INFO : Show hidden gameplay values (rank) on top of the stage
+    pub debug: bool,
 
     current_scene: Option<Box<dyn Scene>>,
     old_scene: Option<Box<dyn Scene>>,
 
-    pub score: ScoreData,
     pub controls: Controls,
+    pub spell_history: SpellHistory,
+    pub save: SaveData,
 }
 
 impl<'a> State<'a> {
@@ -41,9 +46,18 @@ impl<'a> State<'a> {
 
             bgm_volume: 1.0,
             sfx_volume: 0.6,
+            interpolation: true,
+            debug: false,
 
-            score: ScoreData::default(),
             controls: init_controls(),
+            spell_history: SpellHistory::load(SPELL_HISTORY_PATH).unwrap_or_else(|err| {
+                eprintln!("[-] Failed to load spell history : {}", err);
+                SpellHistory::default()
+            }),
+            save: SaveData::load(SAVE_PATH).unwrap_or_else(|err| {
+                eprintln!("[-] Failed to load save data : {}", err);
+                SaveData::default()
+            }),
         }
     }
 
@@ -1,32 +1,70 @@
+use std::f32::consts::{PI, TAU};
+
 use hecs::{Entity, World};
 use num_complex::Complex;
 use raylib::prelude::*;
 
 use crate::{
-    assets::{Assets, AudioAssets},
+    assets::{sfx::SoundEffect, Assets, AudioAssets},
     cmpx,
     components::{
-        AttackMove, BeenOnScreen, Boss, BossMoves, Bullet, CircleHitbox, Controllable, Damage,
-        DieOffScreen, Enemy, Focusable, Hitpoint, InvulnerableDelay, MoveParams, Player,
-        PlayerAttack, RotatingBgBoss, Sprite, Transform2D, Wanderable,
+        AttackMove, BeenOnScreen, Bomb, Boss, BossDefeat, BossMove, BossMoves, Bullet,
+        BulletAction, BulletMotion, BulletSetup, BulletTask, BulletTimeline, CircleHitbox,
+        Controllable, Damage, DieOffScreen, Drops, Dying, Enemy, EnemyAttack, Focusable, Grazed,
+        Hitpoint, Homing, InvulnerableDelay, Item, ItemKind, Laser, LaserPhase, LaserShape,
+        Magnetized, MoveParams, MovementPath, Particle, PhaseIntro, Player, PlayerAttack,
+        PlayerOption, PlayerSpells, Portrait, PreviousPosition, RotatingBgBoss, Sprite,
+        Transform2D, Wanderable,
+    },
+    controls::{Action, ActionState},
+    entity::{
+        create_enemy_bullet, create_item, create_laser, create_particle, create_player_option,
+        PLAYER_ACCELERATION,
     },
-    controls::Action,
-    entity::create_enemy_bullet,
     math::{ComplexExt, ToVec2},
+    score::MAX_POWER,
+    simulation::{Banner, Cutin, Simulation, SpellCardEvent, CUTIN_DURATION, PLAY_FIELD},
     state::State,
-    utility::get_sprite_coord,
+    utility::{get_sprite_coord, random::Rng},
     vec2,
 };
 
+fn interpolate(
+    transform: &Transform2D,
+    previous: Option<&PreviousPosition>,
+    alpha: f32,
+) -> Complex<f32> {
+    match previous {
+        Some(previous) => previous.0.lerp(&transform.position, alpha),
+        None => transform.position,
+    }
+}
+
+pub fn store_previous_position(world: &World) {
+    world
+        .query::<(&Transform2D, &mut PreviousPosition)>()
+        .iter()
+        .for_each(|(_, (t, p))| {
+            p.0 = t.position;
+        });
+}
+
 pub fn draw_sprites_system(
     world: &World,
     state: &State,
+    alpha: f32,
     d: &mut RaylibMode2D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
 ) {
     world
-        .query::<(&Sprite, &Transform2D, Option<&InvulnerableDelay>)>()
+        .query::<(
+            &Sprite,
+            &Transform2D,
+            Option<&PreviousPosition>,
+            Option<&InvulnerableDelay>,
+        )>()
         .iter()
-        .for_each(|(_, (s, t, i))| {
+        .for_each(|(_, (s, t, p, i))| {
+            let position = interpolate(t, p, alpha);
             let color: Color = if i.is_some() {
                 let i = i.unwrap();
                 let blink_duration: f32 = 0.2;
@@ -41,23 +79,31 @@ pub fn draw_sprites_system(
             d.draw_texture_pro(
                 &state.assets.get(s.name),
                 s.src,
-                Rectangle::new(t.position().re, t.position().im, s.src.width, s.src.height),
-                Vector2::new(s.src.width / 2., s.src.height / 2.),
+                Rectangle::new(
+                    position.re,
+                    position.im,
+                    s.src.width * t.scale.x,
+                    s.src.height * t.scale.y,
+                ),
+                Vector2::new(s.src.width * t.scale.x / 2., s.src.height * t.scale.y / 2.),
                 t.rotation,
                 color,
             );
         });
 }
 
-pub fn update_boss_attack(world: &mut World, state: &mut State, d: &RaylibDrawHandle) {
-    let players = world
+pub fn update_boss_attack(sim: &mut Simulation, delta: f32) {
+    let players = sim
+        .world
         .query::<(&Player, &Controllable, &Transform2D)>()
         .iter()
         .map(|(id, (_, _, transform))| (id.clone(), transform.clone()))
         .collect::<Vec<_>>();
 
-    let mut boss = world
+    let mut boss = sim
+        .world
         .query::<(&Boss, &Enemy, &Transform2D, &BossMoves)>()
+        .without::<&PhaseIntro>()
         .iter()
         .map(|(id, (_, _, transform, boss))| {
             (
@@ -73,6 +119,12 @@ pub fn update_boss_attack(world: &mut World, state: &mut State, d: &RaylibDrawHa
 
     boss.iter_mut().for_each(|(id, transform, boss)| {
         if let Some(ref mut attack) = boss {
+            if let Some(name) = attack.spell_name() {
+                if sim.spell_card.is_none() {
+                    sim.declare_spell_card(name);
+                }
+            }
+
             if let Some(player) = players.first() {
                 let attack_move = match attack {
                     crate::components::BossMove::Spells {
@@ -80,6 +132,7 @@ pub fn update_boss_attack(world: &mut World, state: &mut State, d: &RaylibDrawHa
                         timeout,
                         hp,
                         attack,
+                        ..
                     } => attack,
                     crate::components::BossMove::NonSpells {
                         timeout,
@@ -87,32 +140,107 @@ pub fn update_boss_attack(world: &mut World, state: &mut State, d: &RaylibDrawHa
                         attack,
                     } => attack,
                 };
-                handle_fire_bullet(
-                    world,
-                    &id,
-                    &attack_move,
-                    transform.position,
-                    player.1.position,
-                    d,
-                    state,
-                );
-                attack.update_cooldown(d.get_frame_time());
+                handle_fire_bullet(sim, &id, attack_move, transform.position, player.1.position);
+                attack.update_cooldown(delta);
                 let timeout = attack.is_timeout();
-                let mut boss_move = world.get::<&mut BossMoves>(*id).unwrap();
+                let mut boss_move = sim.world.get::<&mut BossMoves>(*id).unwrap();
                 // INFO : make every bullet has it's own sound
 
                 if timeout {
-                    state.audio.spell_end.play(state.sfx_volume);
                     boss_move.0.pop_front();
                 } else {
                     *boss_move.0.front_mut().unwrap() = attack.clone();
                 }
+                drop(boss_move);
+
+                // INFO : Running out of time still move on to the next phase, just without
+                // the spell card bonus
+                if timeout {
+                    end_boss_move(sim, *id, attack, false);
+                }
             }
         }
     });
 }
 
-pub fn invulnerable_delay_update(world: &mut World, d: &RaylibDrawHandle) {
+const PHASE_INTRO: f32 = 1.;
+const SPELL_INTRO: f32 = 2.;
+
+pub fn update_phase_intro(world: &mut World, delta: f32) {
+    let done = world
+        .query_mut::<(&mut PhaseIntro, &mut BossMoves)>()
+        .into_iter()
+        .filter_map(|(id, (intro, moves))| {
+            intro.0 -= delta;
+            if intro.0 > 0. {
+                return None;
+            }
+            if let Some(next) = moves.0.front_mut() {
+                next.set_invulnerable(false);
+            }
+            Some(id)
+        })
+        .collect::<Vec<_>>();
+
+    for boss in done {
+        let _ = world.remove_one::<PhaseIntro>(boss);
+    }
+}
+
+// INFO : Called once the current move of the boss is over and already popped from the queue
+fn end_boss_move(sim: &mut Simulation, boss: Entity, ended: &BossMove, defeated: bool) {
+    sim.play_sfx(SoundEffect::SpellEnd);
+    end_spell_card(sim, ended, defeated);
+
+    // INFO : Every bullet left on screen turn into points
+    let bullets = sim
+        .world
+        .query::<(&Enemy, &Bullet, &Transform2D)>()
+        .iter()
+        .map(|(id, (_, _, transform))| (transform.position, id))
+        .collect::<Vec<_>>();
+    cancel_bullets(sim, bullets);
+
+    let next = match sim.world.get::<&mut BossMoves>(boss) {
+        Ok(mut moves) => match moves.0.front_mut() {
+            Some(next) => {
+                next.set_invulnerable(true);
+                Some(next.spell_name().map(str::to_owned))
+            }
+            None => None,
+        },
+        Err(_) => None,
+    };
+    let Some(spell) = next else {
+        start_boss_defeat(sim, boss);
+        return;
+    };
+
+    let intro = if spell.is_some() {
+        SPELL_INTRO
+    } else {
+        PHASE_INTRO
+    };
+    let _ = sim.world.insert_one(boss, PhaseIntro(intro));
+
+    if let Some(name) = spell {
+        sim.declare_spell_card(&name);
+        let portrait = sim
+            .world
+            .get::<&Portrait>(boss)
+            .map(|portrait| portrait.0.clone())
+            .ok();
+        if let Some(portrait) = portrait {
+            sim.cutin = Some(Cutin {
+                name,
+                portrait,
+                lifetime: CUTIN_DURATION,
+            });
+        }
+    }
+}
+
+pub fn invulnerable_delay_update(world: &mut World, delta: f32) {
     let data = world
         .query::<&InvulnerableDelay>()
         .iter()
@@ -121,7 +249,7 @@ pub fn invulnerable_delay_update(world: &mut World, d: &RaylibDrawHandle) {
 
     for id in data {
         let mut i = world.get::<&mut InvulnerableDelay>(id).unwrap();
-        i.0 -= d.get_frame_time();
+        i.0 -= delta;
         let mut should_remove = false;
         if i.0 < 0. {
             should_remove = true;
@@ -136,77 +264,117 @@ pub fn invulnerable_delay_update(world: &mut World, d: &RaylibDrawHandle) {
 pub fn draw_boss_hp(
     world: &World,
     state: &State,
+    alpha: f32,
     d: &mut RaylibMode2D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
 ) {
     let data = world
-        .query::<(&Boss, &Enemy, &Transform2D, &BossMoves)>()
+        .query::<(
+            &Boss,
+            &Enemy,
+            &Transform2D,
+            Option<&PreviousPosition>,
+            &BossMoves,
+        )>()
         .iter()
-        .map(|(_, (_, _, transform, boss))| {
-            (
-                transform.position.clone(),
-                match boss.0.front() {
-                    Some(attack) => Some(attack.clone()),
-                    None => None,
-                },
-            )
+        .filter_map(|(_, (_, _, transform, previous, moves))| {
+            let current = moves.0.front()?.clone();
+            let segments = moves
+                .current_bar()
+                .map(|boss_move| {
+                    let spell = matches!(boss_move, BossMove::Spells { .. });
+                    (boss_move.get_cur_hp(), boss_move.get_max_hp(), spell)
+                })
+                .collect::<Vec<_>>();
+            Some((
+                interpolate(transform, previous, alpha),
+                current,
+                segments,
+                moves.remaining_bars(),
+            ))
         })
         .collect::<Vec<_>>();
 
-    for (t, a) in data {
-        if a.is_some() {
-            let a = a.unwrap();
-            let cur_hp = a.get_cur_hp();
-            let max_hp = a.get_max_hp();
-            let hp_percentage = cur_hp / max_hp;
-            let start_angle = 0.0;
-            let end_angle = 360.0 * hp_percentage;
+    for (t, current, segments, stars) in data {
+        let time = current.get_time().max(0);
+        let color = if time < 10 { Color::RED } else { Color::WHITE };
+        d.draw_text_pro(
+            &state.assets.font,
+            &format!("{:02}", time),
+            Vector2::new(356., 0.),
+            Vector2::new(0., 0.),
+            0.,
+            16.,
+            0.,
+            color,
+        );
 
+        if let Some(name) = current.spell_name() {
             d.draw_text_pro(
                 &state.assets.font,
-                &format!("{}", a.get_time()),
-                Vector2::new(360., 0.),
+                name,
+                Vector2::new(0., 0.),
                 Vector2::new(0., 0.),
                 0.,
-                16.,
+                14.,
                 0.,
                 Color::WHITE,
             );
-            match a {
-                crate::components::BossMove::Spells {
-                    name,
-                    timeout,
-                    hp,
-                    attack,
-                } => {
-                    d.draw_text_pro(
-                        &state.assets.font,
-                        &name,
-                        Vector2::new(0., 0.),
-                        Vector2::new(0., 0.),
-                        0.,
-                        14.,
-                        0.,
-                        Color::WHITE,
-                    );
-                }
-                _ => {}
-            }
+        }
 
-            d.draw_ring(
-                t.to_vec2(),
-                42.,
-                40.,
-                start_angle,
-                end_angle,
-                32,
-                Color::new(255, 0, 0, 200),
+        for i in 0..stars {
+            d.draw_poly(
+                Vector2::new(8. + 12. * i as f32, 24.),
+                5,
+                5.,
+                -90.,
+                Color::new(255, 220, 120, 220),
             );
         }
+
+        // INFO : The spell card keep the start of the ring, the nonspell before it is the
+        // rest so the ring shrink down to the spell once the nonspell is gone
+        let total = segments.iter().map(|(_, max, _)| max).sum::<f32>();
+        let mut start_angle = 0.;
+        for (cur_hp, max_hp, spell) in segments.iter().rev() {
+            let span = 360. * max_hp / total;
+            let end_angle = start_angle + span * (cur_hp / max_hp).clamp(0., 1.);
+            let color = if *spell {
+                Color::new(255, 0, 0, 200)
+            } else {
+                Color::new(255, 255, 255, 200)
+            };
+            d.draw_ring(t.to_vec2(), 42., 40., start_angle, end_angle, 32, color);
+            start_angle += span;
+        }
     }
 }
 
-pub fn update_collision(world: &mut World, state: &mut State) {
-    let players = world
+fn hitbox_center(transform: &Transform2D, hitbox: &CircleHitbox) -> Complex<f32> {
+    transform.position + cmpx!(hitbox.offset.x, hitbox.offset.y)
+}
+
+pub fn update_collision(sim: &mut Simulation) {
+    sim.bullet_grid.clear();
+    sim.world
+        .query::<(&Enemy, &Bullet, &Transform2D, &CircleHitbox)>()
+        .iter()
+        .for_each(|(id, (_, _, transform, hitbox))| {
+            sim.bullet_grid
+                .insert(hitbox_center(transform, hitbox), hitbox.radius, id);
+        });
+
+    sim.enemy_grid.clear();
+    sim.world
+        .query::<(&Enemy, &Transform2D, &CircleHitbox)>()
+        .without::<&Bullet>()
+        .iter()
+        .for_each(|(id, (_, transform, hitbox))| {
+            sim.enemy_grid
+                .insert(hitbox_center(transform, hitbox), hitbox.radius, id);
+        });
+
+    let player = sim
+        .world
         .query::<(
             &Player,
             &Controllable,
@@ -214,131 +382,623 @@ pub fn update_collision(world: &mut World, state: &mut State) {
             &CircleHitbox,
             Option<&InvulnerableDelay>,
         )>()
+        .without::<&Dying>()
+        .iter()
+        .map(|(id, (_, _, transform, hitbox, i))| (id, *transform, *hitbox, i.is_none()))
+        .next();
+
+    if let Some((player, transform, hitbox, mut vulnerable)) = player {
+        let center = hitbox_center(&transform, &hitbox);
+        let nearby = sim
+            .bullet_grid
+            .query(center, hitbox.radius + GRAZE_RADIUS)
+            .collect::<Vec<_>>();
+
+        for (position, radius, bullet) in nearby {
+            if !vulnerable {
+                break;
+            }
+
+            let reach = hitbox.radius + radius;
+            if position.distance_squared(&center) <= reach * reach {
+                let _ = sim.world.despawn(bullet);
+                hit_player(sim, player);
+                vulnerable = false;
+            } else if let Ok(false) = sim.world.satisfies::<&Grazed>(bullet) {
+                let _ = sim.world.insert_one(bullet, Grazed);
+                graze(sim, center);
+            }
+        }
+
+        // INFO : A laser is not consumed when it hit
+        let lasered = vulnerable
+            && sim
+                .world
+                .query::<(&Laser, &Transform2D)>()
+                .iter()
+                .filter(|(_, (laser, _))| laser.width() > 0.)
+                .any(|(_, (laser, laser_transform))| {
+                    laser
+                        .segments(laser_transform.position)
+                        .into_iter()
+                        .any(|(a, b)| {
+                            hitbox.is_intersect_segment(&transform, a, b, laser.width() / 2.)
+                        })
+                });
+        if lasered {
+            hit_player(sim, player);
+        }
+    }
+
+    let player_bullets = sim
+        .world
+        .query::<(&Player, &Bullet, &Transform2D, &CircleHitbox, &Damage)>()
         .iter()
-        .map(|(id, (_, _, transform, hitbox, i))| {
+        .map(|(id, (_, _, transform, hitbox, damage))| {
             (
-                id.clone(),
-                transform.clone(),
-                hitbox.clone(),
-                match i {
-                    Some(_) => false,
-                    None => true,
-                },
+                id,
+                hitbox_center(transform, hitbox),
+                hitbox.radius,
+                damage.0,
             )
         })
         .collect::<Vec<_>>();
 
-    let enemies = world
-        .query::<(&Enemy, &Transform2D, &CircleHitbox)>()
-        .without::<&Bullet>()
-        .without::<&Boss>()
-        .iter()
-        .map(|(id, (_, transform, hitbox))| (id.clone(), transform.clone(), hitbox.clone()))
+    for (bullet, center, radius, damage) in player_bullets {
+        // INFO : The grid still hold the enemy that died earlier this tick
+        let target = sim
+            .enemy_grid
+            .query(center, radius)
+            .find(|(_, _, enemy)| sim.world.contains(*enemy));
+
+        if let Some((_, _, enemy)) = target {
+            let _ = sim.world.despawn(bullet);
+            damage_target(sim, enemy, damage);
+        }
+    }
+}
+
+fn damage_target(sim: &mut Simulation, enemy: Entity, damage: f32) {
+    if let Ok(true) = sim.world.satisfies::<&BossMoves>(enemy) {
+        damage_boss(sim, enemy, damage);
+    } else {
+        damage_enemy(sim, enemy, damage);
+    }
+}
+
+const BULLET_CLEAR_SCORE: u64 = 100;
+
+// INFO : Cleared bullet are turned into points, the grid is the one built by update_collision
+// this tick so anything despawned since is skipped
+fn clear_bullets(sim: &mut Simulation, position: Complex<f32>, radius: f32) {
+    let cleared = sim
+        .bullet_grid
+        .query(position, radius)
+        .filter(|(_, _, bullet)| sim.world.contains(*bullet))
+        .map(|(position, _, bullet)| (position, bullet))
         .collect::<Vec<_>>();
+    cancel_bullets(sim, cleared);
+}
 
-    let player_bullets = world
-        .query::<(&Player, &Bullet, &Transform2D, &CircleHitbox, &Damage)>()
+fn cancel_bullets(sim: &mut Simulation, bullets: Vec<(Complex<f32>, Entity)>) {
+    for (position, bullet) in bullets {
+        let _ = sim.world.despawn(bullet);
+        sim.score.score += BULLET_CLEAR_SCORE;
+        create_particle(
+            &mut sim.world,
+            position,
+            cmpx!(0., -60.),
+            Sprite::new("commons_sprite", 0, 1, 32., 32.),
+            0.3,
+        );
+    }
+}
+
+fn enemies_in(sim: &Simulation, position: Complex<f32>, radius: f32) -> Vec<Entity> {
+    sim.enemy_grid
+        .query(position, radius)
+        .map(|(_, _, enemy)| enemy)
+        .filter(|enemy| sim.world.contains(*enemy))
+        .collect()
+}
+
+pub fn update_bombs(sim: &mut Simulation, delta: f32) {
+    let player = sim
+        .world
+        .query::<(&Player, &Controllable, &Transform2D)>()
         .iter()
-        .map(|(id, (_, _, transform, hitbox, damage))| {
-            (id.clone(), transform.clone(), hitbox.clone(), damage.0)
+        .map(|(_, (_, _, transform))| transform.position)
+        .next();
+
+    let bombs = sim
+        .world
+        .query_mut::<(&mut Bomb, &mut Transform2D)>()
+        .into_iter()
+        .map(|(id, (bomb, transform))| {
+            bomb.lifetime -= delta;
+            if let (true, Some(player)) = (bomb.follow, player) {
+                transform.position = player;
+            }
+            (id, *bomb, transform.position)
         })
         .collect::<Vec<_>>();
 
-    let enemy_bullets = world
-        .query::<(&Enemy, &Bullet, &Transform2D, &CircleHitbox)>()
+    for (id, bomb, position) in bombs {
+        clear_bullets(sim, position, bomb.radius);
+
+        let touched = enemies_in(sim, position, bomb.radius);
+        for enemy in &touched {
+            damage_target(sim, *enemy, bomb.damage * delta);
+        }
+
+        match bomb.blast {
+            Some((radius, damage)) if !touched.is_empty() || bomb.lifetime <= 0. => {
+                let _ = sim.world.despawn(id);
+                clear_bullets(sim, position, radius);
+                for enemy in enemies_in(sim, position, radius) {
+                    damage_target(sim, enemy, damage);
+                }
+                explosion(sim, position);
+            }
+            _ if bomb.lifetime <= 0. => {
+                let _ = sim.world.despawn(id);
+            }
+            _ => {}
+        }
+    }
+}
+
+const ENEMY_KILL_SCORE: u64 = 1000;
+
+fn damage_enemy(sim: &mut Simulation, enemy: Entity, damage: f32) {
+    let dead = match sim.world.get::<&mut Hitpoint>(enemy) {
+        Ok(mut hitpoint) => hitpoint.damage(damage),
+        Err(_) => false,
+    };
+
+    if dead {
+        kill_enemy(sim, enemy);
+    }
+}
+
+fn kill_enemy(sim: &mut Simulation, enemy: Entity) {
+    let Ok(position) = sim.world.get::<&Transform2D>(enemy).map(|t| t.position) else {
+        return;
+    };
+    let drops = sim
+        .world
+        .get::<&Drops>(enemy)
+        .map(|drops| drops.0.clone())
+        .unwrap_or_default();
+    let _ = sim.world.despawn(enemy);
+
+    sim.score.score += ENEMY_KILL_SCORE;
+    explosion(sim, position);
+    drop_items(sim, position, &drops);
+}
+
+fn drop_items(sim: &mut Simulation, position: Complex<f32>, drops: &[ItemKind]) {
+    // INFO : Bigger drop are scattered wider so they don't overlap
+    let scatter = 16. + drops.len() as f32 * 2.;
+    for kind in drops {
+        let offset = cmpx!(
+            sim.rng.range_f32(-scatter, scatter),
+            sim.rng.range_f32(-scatter, scatter)
+        );
+        create_item(&mut sim.world, position + offset, *kind);
+    }
+}
+
+pub fn magnetize_items(world: &mut World) {
+    let items = world
+        .query::<&Item>()
+        .without::<&Magnetized>()
         .iter()
-        .map(|(id, (_, _, transform, hitbox))| (id.clone(), transform.clone(), hitbox.clone()))
+        .map(|(id, _)| id)
         .collect::<Vec<_>>();
 
-    let boss = world
-        .query::<(&Boss, &Enemy, &Transform2D, &CircleHitbox)>()
+    for id in items {
+        let _ = world.insert_one(id, Magnetized);
+    }
+}
+
+// INFO : Point of collection, every item is pulled to the player when it is above this line
+const POC_LINE: f32 = 128.;
+const ITEM_COLLECT_RADIUS: f32 = 20.;
+const FOCUS_MAGNET_RADIUS: f32 = 64.;
+const MAGNET_SPEED: f32 = 480.;
+const POWER_ITEM: f32 = 0.05;
+const ITEM_SCORE: u64 = 10;
+
+pub fn update_items(sim: &mut Simulation, focused: bool) {
+    let Some(player) = sim
+        .world
+        .query::<(
+            &Player,
+            &Controllable,
+            &Transform2D,
+            Option<&InvulnerableDelay>,
+        )>()
+        .iter()
+        .map(|(_, (_, _, transform, invulnerable))| (transform.position, invulnerable.is_none()))
+        .next()
+    else {
+        return;
+    };
+    let (player, alive) = player;
+
+    // INFO : Not while respawning, the player is teleported back and would suck everything
+    if alive && player.im < POC_LINE {
+        magnetize_items(&mut sim.world);
+    }
+
+    let items = sim
+        .world
+        .query::<(&Item, &Transform2D, &CircleHitbox, Option<&Magnetized>)>()
         .iter()
-        .map(|(id, (_, _, transform, hitbox))| (id.clone(), transform.clone(), hitbox.clone()))
+        .map(|(id, (item, transform, hitbox, magnetized))| {
+            (
+                id,
+                item.0,
+                transform.position,
+                hitbox.radius,
+                magnetized.is_some(),
+            )
+        })
         .collect::<Vec<_>>();
 
-    {
-        if let Some(player) = players.first() {
-            for enemy_bullet in enemy_bullets {
-                if player
-                    .2
-                    .is_intersect(&player.1, &enemy_bullet.1, &enemy_bullet.2)
-                    && player.3
-                {
-                    let _ = world.despawn(enemy_bullet.0);
-                    state.score.life -= 1;
-                    state.audio.death_sfx.play(state.sfx_volume);
-                    let _ = world.insert_one(player.0, InvulnerableDelay(2.)).unwrap();
-                    let mut a = world.get::<&mut Transform2D>(player.0).unwrap();
-                    a.position = cmpx!(150., 400.);
-                }
+    for (id, kind, position, radius, magnetized) in items {
+        let distance = position.distance_squared(&player);
+        let reach = ITEM_COLLECT_RADIUS + radius;
+        if distance <= reach * reach {
+            let _ = sim.world.despawn(id);
+            collect_item(sim, kind, position, magnetized);
+            continue;
+        }
+
+        let magnetized = magnetized || (focused && distance <= FOCUS_MAGNET_RADIUS.powi(2));
+        if magnetized {
+            if let Ok(mut movement) = sim.world.get::<&mut MoveParams>(id) {
+                *movement = MoveParams::move_linear(position.dir(&player) * MAGNET_SPEED);
             }
         }
     }
-    {
-        for player_bullet in &player_bullets {
-            for boss in &boss {
-                if player_bullet
-                    .2
-                    .is_intersect(&player_bullet.1, &boss.1, &boss.2)
-                {
-                    let _ = world.despawn(player_bullet.0);
-
-                    // TODO : Make the damage based on bullet type
-                    match world.satisfies::<&Hitpoint>(boss.0) {
-                        Ok(exist) if exist => {
-                            world
-                                .get::<&mut Hitpoint>(boss.0)
-                                .unwrap()
-                                .damage(player_bullet.3);
-                        }
-                        _ => {}
-                    };
+}
 
-                    let mut despawn = false;
-                    {
-                        let mut ab = world.query_one::<&mut BossMoves>(boss.0).unwrap();
-                        let a = ab.get().unwrap();
-                        despawn = match a.0.front_mut() {
-                            Some(attack) => {
-                                attack.damage(player_bullet.3);
-                                if attack.is_dead() {
-                                    state.audio.spell_end.play(state.sfx_volume);
-                                    a.0.pop_front();
-                                }
-                                false
-                            }
-                            None => true,
-                        };
-                    }
+fn collect_item(sim: &mut Simulation, kind: ItemKind, position: Complex<f32>, full: bool) {
+    sim.score.score += ITEM_SCORE;
+    match kind {
+        ItemKind::Power => sim.score.add_power(POWER_ITEM),
+        ItemKind::Point => {
+            // INFO : Full value above the POC line, down to half at the bottom of the field
+            let ratio = if full || position.im < POC_LINE {
+                1.
+            } else {
+                1. - 0.5 * ((position.im - POC_LINE) / (PLAY_FIELD.1 - POC_LINE)).min(1.)
+            };
+            sim.score.score += (sim.score.value as f32 * ratio) as u64;
+            sim.score.value += 10;
+            sim.score.point_items += 1;
+        }
+        ItemKind::BombPiece => sim.score.add_bomb_piece(),
+        ItemKind::LifePiece => sim.score.add_life_piece(),
+    }
+    sim.play_sfx(SoundEffect::Select);
+}
 
-                    if despawn {
-                        let _ = world.despawn(boss.0);
-                    }
-                }
+fn explosion(sim: &mut Simulation, position: Complex<f32>) {
+    sim.play_sfx(SoundEffect::EnemyDeath);
+
+    for i in 0..8 {
+        let angle = i as f32 / 8. * TAU + sim.rng.range_f32(-0.2, 0.2);
+        let velocity = Complex::cdir(angle) * sim.rng.range_f32(160., 320.);
+        create_particle(
+            &mut sim.world,
+            position,
+            velocity,
+            Sprite::new("commons_sprite", 0, 1, 32., 32.),
+            0.4,
+        );
+    }
+}
+
+const SPELL_BONUS: u64 = 1_000_000;
+const BANNER_LIFETIME: f32 = 3.;
+
+// INFO : The bonus decay with the time taken, down to a third of it right before the timeout
+fn spell_bonus(progress: f32) -> u64 {
+    let bonus = SPELL_BONUS as f32 * (1. + 2. * progress.clamp(0., 1.)) / 3.;
+    (bonus as u64 / 10) * 10
+}
+
+fn end_spell_card(sim: &mut Simulation, boss_move: &BossMove, defeated: bool) {
+    let Some(card) = sim.spell_card.take() else {
+        return;
+    };
+
+    // INFO : A survival card only end on timeout, lasting through it is the capture
+    let survival = boss_move.is_survival();
+    if card.failed || !(defeated || survival) {
+        sim.banner = Some(Banner {
+            title: "Bonus Failed...".to_owned(),
+            bonus: None,
+            lifetime: BANNER_LIFETIME,
+        });
+        return;
+    }
+
+    let progress = if survival { 1. } else { boss_move.progress() };
+    let bonus = spell_bonus(progress);
+    sim.score.score += bonus;
+    sim.spell_events.push(SpellCardEvent::Captured(card.name));
+    sim.banner = Some(Banner {
+        title: "Get Spell Card Bonus!!".to_owned(),
+        bonus: Some(bonus),
+        lifetime: BANNER_LIFETIME,
+    });
+}
+
+fn damage_boss(sim: &mut Simulation, boss: Entity, damage: f32) {
+    // TODO : Make the damage based on bullet type
+    if let Ok(mut hitpoint) = sim.world.get::<&mut Hitpoint>(boss) {
+        hitpoint.damage(damage);
+    }
+
+    let mut defeated = None;
+    if let Ok(mut moves) = sim.world.get::<&mut BossMoves>(boss) {
+        if let Some(attack) = moves.0.front_mut() {
+            attack.damage(damage);
+            if attack.is_dead() {
+                defeated = moves.0.pop_front();
             }
         }
     }
+
+    if let Some(defeated) = defeated {
+        end_boss_move(sim, boss, &defeated, true);
+
+        let position = sim
+            .world
+            .get::<&Transform2D>(boss)
+            .map(|transform| transform.position)
+            .unwrap_or_default();
+        let mut drops = sim
+            .world
+            .get::<&Drops>(boss)
+            .map(|drops| drops.0.clone())
+            .unwrap_or_default();
+        if let BossMove::Spells { .. } = defeated {
+            drops.push(ItemKind::BombPiece);
+        }
+        drop_items(sim, position, &drops);
+        magnetize_items(&mut sim.world);
+    }
+}
+
+const BOSS_DEFEAT_TIME: f32 = 2.;
+
+// INFO : The boss stop moving and can't be hit anymore, it blow up in update_boss_defeat
+fn start_boss_defeat(sim: &mut Simulation, boss: Entity) {
+    let _ = sim.world.remove::<(Wanderable, CircleHitbox)>(boss);
+    if let Ok(mut movement) = sim.world.get::<&mut MoveParams>(boss) {
+        *movement = MoveParams::move_linear(cmpx!(0.));
+    }
+    let _ = sim.world.insert_one(boss, BossDefeat(BOSS_DEFEAT_TIME));
+    sim.stop_bgm();
+}
+
+pub fn update_boss_defeat(sim: &mut Simulation, delta: f32) {
+    let bosses = sim
+        .world
+        .query_mut::<(&mut BossDefeat, &Transform2D)>()
+        .into_iter()
+        .map(|(id, (defeat, transform))| {
+            defeat.0 -= delta;
+            (id, transform.position, defeat.0 <= 0.)
+        })
+        .collect::<Vec<_>>();
+
+    for (boss, position, done) in bosses {
+        if !done {
+            // INFO : Small blast all over the boss while it's going down
+            // INFO : u64::is_multiple_of need rust 1.87
+            #[allow(clippy::manual_is_multiple_of)]
+            if sim.ticks % 12 == 0 {
+                let offset = cmpx!(sim.rng.range_f32(-24., 24.), sim.rng.range_f32(-32., 32.));
+                explosion(sim, position + offset);
+            }
+            continue;
+        }
+
+        explosion(sim, position);
+        for i in 0..32 {
+            let angle = i as f32 / 32. * TAU;
+            let velocity = Complex::cdir(angle) * sim.rng.range_f32(200., 600.);
+            create_particle(
+                &mut sim.world,
+                position,
+                velocity,
+                Sprite::new("commons_sprite", 0, 1, 32., 32.),
+                1.,
+            );
+        }
+
+        let mut drops = vec![ItemKind::Point; 16];
+        drops.push(ItemKind::LifePiece);
+        drop_items(sim, position, &drops);
+        magnetize_items(&mut sim.world);
+
+        let _ = sim.world.despawn(boss);
+        sim.boss_fight = false;
+    }
+}
+
+const GRAZE_RADIUS: f32 = 16.;
+const GRAZE_SCORE: u64 = 500;
+
+fn graze(sim: &mut Simulation, position: Complex<f32>) {
+    sim.score.graze += 1;
+    sim.score.rank.graze();
+    sim.score.score += GRAZE_SCORE;
+    sim.play_sfx(SoundEffect::Graze);
+
+    for _ in 0..3 {
+        let velocity = Complex::cdir(sim.rng.next_f32() * TAU) * sim.rng.range_f32(120., 240.);
+        create_particle(
+            &mut sim.world,
+            position,
+            velocity,
+            Sprite::new("commons_sprite", 0, 1, 32., 32.),
+            0.25,
+        );
+    }
+}
+
+const DEATH_POWER_LOSS: f32 = 0.5;
+const DEATH_POWER_ITEMS: usize = 7;
+
+// INFO : Bombing during the deathbomb window cost this many spell instead of one
+const DEATHBOMB_COST: i8 = 2;
+
+fn hit_player(sim: &mut Simulation, player: Entity) {
+    sim.play_sfx(SoundEffect::Death);
+    sim.fail_spell_card();
+    if sim.deathbomb_window > 0. {
+        let _ = sim.world.insert_one(player, Dying(sim.deathbomb_window));
+    } else {
+        kill_player(sim, player);
+    }
+}
+
+fn deathbomb(sim: &mut Simulation, player: Entity, spells: PlayerSpells, position: Complex<f32>) {
+    let active = sim.world.query::<&Bomb>().iter().next().is_some();
+    if sim.score.spell < DEATHBOMB_COST || active {
+        return;
+    }
+
+    let _ = sim.world.remove_one::<Dying>(player);
+    sim.score.spell -= DEATHBOMB_COST - 1;
+    (spells.spawn(position))(sim);
+}
+
+pub fn update_rank(sim: &mut Simulation, delta: f32) {
+    let score = &mut sim.score;
+    score
+        .rank
+        .update(score.power, MAX_POWER, score.score, delta);
+}
+
+pub fn update_dying(sim: &mut Simulation, delta: f32) {
+    let dead = sim
+        .world
+        .query_mut::<&mut Dying>()
+        .into_iter()
+        .filter_map(|(id, dying)| {
+            dying.0 -= delta;
+            (dying.0 <= 0.).then_some(id)
+        })
+        .collect::<Vec<_>>();
+
+    for player in dead {
+        let _ = sim.world.remove_one::<Dying>(player);
+        kill_player(sim, player);
+    }
+}
+
+fn kill_player(sim: &mut Simulation, player: Entity) {
+    sim.score.life -= 1;
+    sim.score.rank.death();
+    let _ = sim.world.insert_one(player, InvulnerableDelay(2.));
+
+    let mut death_position = None;
+    if let Ok(mut transform) = sim.world.get::<&mut Transform2D>(player) {
+        death_position = Some(transform.position);
+        transform.position = cmpx!(150., 400.);
+    }
+
+    // INFO : Part of the lost power is scattered where the player died so it can be recovered
+    sim.score.power = (sim.score.power - DEATH_POWER_LOSS).max(1.);
+    if let Some(position) = death_position {
+        drop_items(sim, position, &[ItemKind::Power; DEATH_POWER_ITEMS]);
+
+        for i in 0..16 {
+            let angle = i as f32 / 16. * TAU;
+            let velocity = Complex::cdir(angle) * sim.rng.range_f32(240., 480.);
+            create_particle(
+                &mut sim.world,
+                position,
+                velocity,
+                Sprite::new("commons_sprite", 0, 1, 32., 32.),
+                0.6,
+            );
+        }
+    }
+
+    // INFO : Respawn on a clean screen
+    let bullets = sim
+        .world
+        .query::<(&Enemy, &Bullet)>()
+        .iter()
+        .map(|(id, _)| id)
+        .collect::<Vec<_>>();
+    for bullet in bullets {
+        let _ = sim.world.despawn(bullet);
+    }
 }
 
 pub fn update_cooldown_attack(attack: &mut AttackMove, d: f32) {
     match attack {
-        AttackMove::AtPlayer { cooldown, .. } => cooldown.0.update(d),
-        AttackMove::Circle { cooldown, .. } => cooldown.0.update(d),
+        AttackMove::AtPlayer { cooldown, .. }
+        | AttackMove::Circle { cooldown, .. }
+        | AttackMove::Spiral { cooldown, .. }
+        | AttackMove::Arc { cooldown, .. }
+        | AttackMove::Spray { cooldown, .. }
+        | AttackMove::AimedRing { cooldown, .. }
+        | AttackMove::Stacked { cooldown, .. }
+        | AttackMove::BurstReaim { cooldown, .. }
+        | AttackMove::Laser { cooldown, .. } => cooldown.0.update(d),
         AttackMove::Multiple(attacks) => attacks
             .iter_mut()
             .for_each(|attack| update_cooldown_attack(attack, d)),
     };
 }
 
+fn fire_enemy_bullet(
+    sim: &mut Simulation,
+    position: Complex<f32>,
+    velocity: Complex<f32>,
+    move_params: MoveParams,
+    setup: &BulletSetup,
+) -> Entity {
+    let transform = Transform2D {
+        scale: vec2!(1.),
+        rotation: velocity.rot(),
+        position,
+    };
+    sim.play_sfx(SoundEffect::Shot1);
+    let bullet = create_enemy_bullet(&mut sim.world, transform, setup.0, move_params, setup.1);
+    if !setup.2.is_empty() {
+        let _ = sim
+            .world
+            .insert_one(bullet, BulletTimeline::new(setup.2.clone()));
+    }
+    bullet
+}
+
+// INFO : Angle of the i-th bullet of an aimed fan, centred on the aim whatever the count
+fn fan_angle(i: u16, num: u16, spread: f32) -> f32 {
+    (i as f32 - (num - 1) as f32 / 2.) * spread
+}
+
 fn handle_fire_bullet(
-    world: &mut World,
+    sim: &mut Simulation,
     id: &Entity,
-    attack_move: &AttackMove,
+    attack_move: &mut AttackMove,
     transform: Complex<f32>,
     player: Complex<f32>,
-    d: &RaylibDrawHandle,
-    state: &mut State,
 ) {
+    let aim = (player - transform).arg();
+    let rank = sim.score.rank.modifier(sim.score.difficulty);
     match attack_move {
         AttackMove::AtPlayer {
             num,
@@ -348,42 +1008,32 @@ fn handle_fire_bullet(
             cooldown,
             setup,
         } if cooldown.0.completed() => {
+            // INFO : Only the speed follow rank, the bullet count is already set by the difficulty
+            let speed = *speed * rank.speed;
             if *total_shoot <= 0 {
                 return;
             }
 
             if *num > 1 {
-                for i in 0..*num as i32 {
-                    let rand_x = d.get_random_value::<i32>(1..100) as f32 / 10000.;
-                    let rand_y = d.get_random_value::<i32>(1..100) as f32 / 10000.;
-                    let angle = (i - 1) as f32 * spread;
+                for i in 0..*num {
+                    let rand_x = sim.rng.get_random_value::<i32>(1..100) as f32 / 10000.;
+                    let rand_y = sim.rng.get_random_value::<i32>(1..100) as f32 / 10000.;
+                    let angle = fan_angle(i, *num, *spread);
                     let dir = transform.dir(&player) * Complex::cdir(angle) * speed
                         + cmpx!(rand_x, rand_y);
                     let move_params = MoveParams::move_linear(dir);
-                    let transform = Transform2D {
-                        rotation: dir.rot(),
-                        scale: vec2!(0.05),
-                        position: transform,
-                    };
-                    state.audio.shot1.play(state.sfx_volume);
-                    create_enemy_bullet(world, transform, setup.0.clone(), move_params, setup.1);
+                    fire_enemy_bullet(sim, transform, dir, move_params, setup);
                 }
                 return;
             }
 
             let dir = transform.dir(&player) * speed;
             let move_params = MoveParams::move_linear(dir);
-            let transform = Transform2D {
-                scale: vec2!(1.),
-                rotation: dir.rot(),
-                position: transform,
-            };
-            state.audio.shot1.play(state.sfx_volume);
-            create_enemy_bullet(world, transform, setup.0.clone(), move_params, setup.1);
+            fire_enemy_bullet(sim, transform, dir, move_params, setup);
         }
-        AttackMove::Multiple(moves) => moves.iter().for_each(|attack_move| {
-            handle_fire_bullet(world, id, attack_move, transform, player, d, state)
-        }),
+        AttackMove::Multiple(moves) => moves
+            .iter_mut()
+            .for_each(|attack_move| handle_fire_bullet(sim, id, attack_move, transform, player)),
         AttackMove::Circle {
             sides,
             speed,
@@ -392,32 +1042,456 @@ fn handle_fire_bullet(
             cooldown,
             setup,
         } if cooldown.0.completed() => {
-            for side in 0..*sides {
-                let rotation =
-                    (side as f32 / *sides as f32) * std::f32::consts::PI * 2. + *rotation;
+            let sides = rank.count(*sides);
+            let speed = *speed * rank.speed;
+            for side in 0..sides {
+                let rotation = (side as f32 / sides as f32) * TAU + *rotation;
                 let dir = Complex::cdir(rotation) * speed;
-                let move_params = MoveParams::move_linear(dir);
+                fire_enemy_bullet(sim, transform, dir, MoveParams::move_linear(dir), setup);
+            }
+            *rotation += *rotation_per_fire;
+        }
+        AttackMove::Spiral {
+            arms,
+            speed,
+            angular_speed,
+            rotation,
+            cooldown,
+            motion,
+            setup,
+        } if cooldown.0.completed() => {
+            let arms = rank.count(*arms);
+            let speed = *speed * rank.speed;
+            for arm in 0..arms {
+                let angle = (arm as f32 / arms as f32) * TAU + *rotation;
+                let dir = Complex::cdir(angle) * speed;
+                let move_params = motion.to_move_params(dir, player);
+                fire_enemy_bullet(sim, transform, dir, move_params, setup);
+            }
+            *rotation += *angular_speed;
+        }
+        AttackMove::Arc {
+            num,
+            speed,
+            width,
+            angle,
+            aimed,
+            cooldown,
+            motion,
+            setup,
+        } if cooldown.0.completed() => {
+            let num = rank.count(*num);
+            let speed = *speed * rank.speed;
+            let base = if *aimed { aim + *angle } else { *angle };
+            for i in 0..num {
+                let t = if num > 1 {
+                    i as f32 / (num - 1) as f32 - 0.5
+                } else {
+                    0.
+                };
+                let dir = Complex::cdir(base + t * *width) * speed;
+                let move_params = motion.to_move_params(dir, player);
+                fire_enemy_bullet(sim, transform, dir, move_params, setup);
+            }
+        }
+        AttackMove::Spray {
+            num,
+            min_speed,
+            max_speed,
+            width,
+            angle,
+            aimed,
+            cooldown,
+            motion,
+            setup,
+        } if cooldown.0.completed() => {
+            let num = rank.count(*num);
+            let min_speed = *min_speed * rank.speed;
+            let max_speed = *max_speed * rank.speed;
+            let base = if *aimed { aim + *angle } else { *angle };
+            for _ in 0..num {
+                let offset = sim.rng.range_f32(-*width / 2., *width / 2.);
+                let speed = sim.rng.range_f32(min_speed, max_speed);
+                let dir = Complex::cdir(base + offset) * speed;
+                let move_params = motion.to_move_params(dir, player);
+                fire_enemy_bullet(sim, transform, dir, move_params, setup);
+            }
+        }
+        AttackMove::AimedRing {
+            sides,
+            speed,
+            cooldown,
+            motion,
+            setup,
+        } if cooldown.0.completed() => {
+            let sides = rank.count(*sides);
+            let speed = *speed * rank.speed;
+            for side in 0..sides {
+                let dir = Complex::cdir(aim + (side as f32 / sides as f32) * TAU) * speed;
+                let move_params = motion.to_move_params(dir, player);
+                fire_enemy_bullet(sim, transform, dir, move_params, setup);
+            }
+        }
+        AttackMove::Stacked {
+            num,
+            layers,
+            min_speed,
+            max_speed,
+            spread,
+            cooldown,
+            motion,
+            setup,
+        } if cooldown.0.completed() => {
+            let num = rank.count(*num);
+            let min_speed = *min_speed * rank.speed;
+            let max_speed = *max_speed * rank.speed;
+            for layer in 0..*layers {
+                let t = if *layers > 1 {
+                    layer as f32 / (*layers - 1) as f32
+                } else {
+                    0.
+                };
+                let speed = min_speed + (max_speed - min_speed) * t;
+                for i in 0..num {
+                    let offset = (i as f32 - (num - 1) as f32 / 2.) * *spread;
+                    let dir = Complex::cdir(aim + offset) * speed;
+                    let move_params = motion.to_move_params(dir, player);
+                    fire_enemy_bullet(sim, transform, dir, move_params, setup);
+                }
+            }
+        }
+        AttackMove::BurstReaim {
+            sides,
+            speed,
+            retention,
+            delay,
+            reaim_speed,
+            cooldown,
+            setup,
+        } if cooldown.0.completed() => {
+            let sides = rank.count(*sides);
+            let speed = *speed * rank.speed;
+            let reaim_speed = *reaim_speed * rank.speed;
+            for side in 0..sides {
+                let dir = Complex::cdir(aim + (side as f32 / sides as f32) * TAU) * speed;
+                let move_params =
+                    MoveParams::move_asymptotic(dir, cmpx!(0.), cmpx!(*retention, 0.));
+                let bullet = fire_enemy_bullet(sim, transform, dir, move_params, setup);
+                let reaim = BulletTask {
+                    at: *delay,
+                    action: BulletAction::Aim {
+                        speed: reaim_speed,
+                        motion: BulletMotion::Linear,
+                    },
+                };
+                let mut timeline = sim
+                    .world
+                    .remove_one::<BulletTimeline>(bullet)
+                    .map(|timeline| timeline.tasks.into())
+                    .unwrap_or_else(|_| Vec::new());
+                timeline.push(reaim);
+                let _ = sim.world.insert_one(bullet, BulletTimeline::new(timeline));
+            }
+        }
+        AttackMove::Laser {
+            num,
+            angle,
+            aimed,
+            spread,
+            cooldown,
+            laser,
+        } if cooldown.0.completed() => {
+            let base = if *aimed { aim + *angle } else { *angle };
+            for i in 0..*num {
+                let offset = (i as f32 - (*num - 1) as f32 / 2.) * *spread;
+                create_laser(&mut sim.world, transform, base + offset, *laser, player);
+            }
+            sim.play_sfx(SoundEffect::Shot1);
+        }
 
-                let transform = Transform2D {
-                    scale: vec2!(1.),
-                    rotation: dir.rot(),
-                    position: transform,
+        AttackMove::AtPlayer { .. }
+        | AttackMove::Circle { .. }
+        | AttackMove::Spiral { .. }
+        | AttackMove::Arc { .. }
+        | AttackMove::Spray { .. }
+        | AttackMove::AimedRing { .. }
+        | AttackMove::Stacked { .. }
+        | AttackMove::BurstReaim { .. }
+        | AttackMove::Laser { .. } => {}
+    }
+}
+
+// INFO : Run the BulletTimeline of every bullet, actions are collected first since
+// splitting need to spawn while the query is borrowed
+pub fn update_bullet_timeline(sim: &mut Simulation, delta: f32) {
+    let player = sim
+        .world
+        .query::<(&Player, &Controllable, &Transform2D)>()
+        .iter()
+        .map(|(_, (_, _, transform))| transform.position)
+        .next();
+
+    let mut pending = Vec::new();
+    let mut finished = Vec::new();
+    sim.world
+        .query::<&mut BulletTimeline>()
+        .iter()
+        .for_each(|(id, timeline)| {
+            let due = timeline.advance(delta);
+            if timeline.is_done() {
+                finished.push(id);
+            }
+            if !due.is_empty() {
+                pending.push((id, due));
+            }
+        });
+
+    for id in finished {
+        let _ = sim.world.remove_one::<BulletTimeline>(id);
+    }
+
+    for (id, actions) in pending {
+        for action in actions {
+            if !sim.world.contains(id) {
+                break;
+            }
+            apply_bullet_action(sim, id, action, player);
+        }
+    }
+}
+
+fn apply_bullet_action(
+    sim: &mut Simulation,
+    id: Entity,
+    action: BulletAction,
+    player: Option<Complex<f32>>,
+) {
+    let Ok((position, rotation)) = sim
+        .world
+        .query_one_mut::<&Transform2D>(id)
+        .map(|transform| (transform.position, transform.rotation))
+    else {
+        return;
+    };
+    // INFO : Transform2D::rotation is the sprite rotation in degree, pointing up at 0
+    let heading = (rotation - 90.).to_radians();
+    let target = player.unwrap_or(position);
+
+    match action {
+        BulletAction::SetVelocity {
+            speed,
+            angle,
+            absolute,
+            motion,
+        } => {
+            let angle = if absolute { angle } else { heading + angle };
+            redirect_bullet(sim, id, Complex::cdir(angle) * speed, motion, target);
+        }
+        BulletAction::Aim { speed, motion } => {
+            let dir = match player {
+                Some(player) => position.dir(&player),
+                None => Complex::cdir(heading),
+            };
+            redirect_bullet(sim, id, dir * speed, motion, target);
+        }
+        BulletAction::Split {
+            num,
+            spread,
+            speed,
+            aimed,
+            motion,
+            setup,
+        } => {
+            let setup = match setup {
+                Some(setup) => *setup,
+                None => {
+                    let Ok((sprite, hitbox)) = sim
+                        .world
+                        .query_one_mut::<(&Sprite, &CircleHitbox)>(id)
+                        .map(|(sprite, hitbox)| (*sprite, *hitbox))
+                    else {
+                        return;
+                    };
+                    BulletSetup(sprite, hitbox, Vec::new())
+                }
+            };
+            let base = match (aimed, player) {
+                (true, Some(player)) => (player - position).arg(),
+                _ => heading,
+            };
+            let _ = sim.world.despawn(id);
+            for i in 0..num {
+                let t = if num > 1 {
+                    i as f32 / (num - 1) as f32 - 0.5
+                } else {
+                    0.
                 };
-                state.audio.shot1.play(state.sfx_volume);
-                create_enemy_bullet(world, transform, setup.0.clone(), move_params, setup.1);
+                let dir = Complex::cdir(base + t * spread) * speed;
+                let move_params = motion.to_move_params(dir, target);
+                fire_enemy_bullet(sim, position, dir, move_params, &setup);
             }
         }
+        BulletAction::SetSprite(sprite) => {
+            let _ = sim.world.insert_one(id, sprite);
+        }
+        BulletAction::SetHitbox(hitbox) => {
+            let _ = sim.world.insert_one(id, hitbox);
+        }
+        BulletAction::Despawn => {
+            let _ = sim.world.despawn(id);
+        }
+    }
+}
 
-        AttackMove::AtPlayer { .. } | AttackMove::Circle { .. } => {}
+fn redirect_bullet(
+    sim: &mut Simulation,
+    id: Entity,
+    velocity: Complex<f32>,
+    motion: BulletMotion,
+    target: Complex<f32>,
+) {
+    if let Ok((transform, move_params)) = sim
+        .world
+        .query_one_mut::<(&mut Transform2D, &mut MoveParams)>(id)
+    {
+        *move_params = motion.to_move_params(velocity, target);
+        // INFO : Keep the heading of a stopped bullet so a relative redirect still make sense
+        if velocity.norm_sqr() > 0. {
+            transform.rotation = velocity.rot();
+        }
     }
 }
 
-pub fn update_movement(world: &World, d: &RaylibDrawHandle<'_>) {
+pub fn update_lasers(world: &mut World, delta: f32) {
+    let mut done = Vec::new();
+    world
+        .query::<(&mut Laser, &Transform2D)>()
+        .iter()
+        .for_each(|(id, (laser, transform))| {
+            laser.elapsed += delta;
+            match laser.setup.shape {
+                LaserShape::Straight { angular_speed, .. } => laser.angle += angular_speed * delta,
+                LaserShape::Curvy { length, .. } => {
+                    laser.points.push_front(transform.position);
+                    laser.points.truncate(length as usize);
+                }
+            }
+            if laser.phase() == LaserPhase::Done {
+                done.push(id);
+            }
+        });
+
+    for id in done {
+        let _ = world.despawn(id);
+    }
+}
+
+pub fn draw_lasers(
+    world: &World,
+    state: &State,
+    alpha: f32,
+    d: &mut RaylibMode2D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
+) {
+    let texture = state.assets.get("commons_sprite");
+    let src = get_sprite_coord(0, 1, 32., 32.);
+    world
+        .query::<(&Laser, &Transform2D, Option<&PreviousPosition>)>()
+        .iter()
+        .for_each(|(_, (laser, t, p))| {
+            // INFO : Warning line is a thin transparent version of the laser
+            let (width, color) = match laser.phase() {
+                LaserPhase::Warning => (2., Color::new(255, 255, 255, 96)),
+                _ => (laser.width().max(2.), Color::WHITE),
+            };
+            for (a, b) in laser.segments(interpolate(t, p, alpha)) {
+                let length = (b - a).norm();
+                if length <= 0. {
+                    continue;
+                }
+                d.draw_texture_pro(
+                    texture,
+                    src,
+                    Rectangle::new(a.re, a.im, length, width),
+                    Vector2::new(0., width / 2.),
+                    (b - a).arg().to_degrees(),
+                    color,
+                );
+            }
+        });
+}
+
+pub fn update_enemy_attack(sim: &mut Simulation, delta: f32) {
+    let Some(player) = sim
+        .world
+        .query::<(&Player, &Controllable, &Transform2D)>()
+        .iter()
+        .map(|(_, (_, _, transform))| transform.position)
+        .next()
+    else {
+        return;
+    };
+
+    let attackers = sim
+        .world
+        .query::<(&Enemy, &Transform2D, &mut EnemyAttack)>()
+        .without::<&Boss>()
+        .iter()
+        .filter_map(|(id, (_, transform, attack))| {
+            if attack.delay > 0. {
+                attack.delay -= delta;
+                return None;
+            }
+            Some((id, transform.position, attack.attack.clone()))
+        })
+        .collect::<Vec<_>>();
+
+    for (id, position, mut attack) in attackers {
+        handle_fire_bullet(sim, &id, &mut attack, position, player);
+        update_cooldown_attack(&mut attack, delta);
+        if let Ok(mut enemy_attack) = sim.world.get::<&mut EnemyAttack>(id) {
+            enemy_attack.attack = attack;
+        }
+    }
+}
+
+pub fn update_movement_path(world: &mut World, delta: f32) {
+    let done = world
+        .query_mut::<(&mut MovementPath, &mut MoveParams)>()
+        .into_iter()
+        .filter_map(|(id, (path, movement))| {
+            if let Some(next) = path.advance(delta) {
+                *movement = next;
+            }
+            path.is_done().then_some(id)
+        })
+        .collect::<Vec<_>>();
+
+    done.into_iter().for_each(|id| {
+        let _ = world.remove_one::<MovementPath>(id);
+    });
+}
+
+pub fn update_particles(world: &mut World, delta: f32) {
+    let pending = world
+        .query_mut::<&mut Particle>()
+        .into_iter()
+        .filter_map(|(id, particle)| {
+            particle.0 -= delta;
+            (particle.0 <= 0.).then_some(id)
+        })
+        .collect::<Vec<_>>();
+
+    pending.into_iter().for_each(|id| {
+        let _ = world.despawn(id);
+    });
+}
+
+pub fn update_movement(world: &World, delta: f32) {
     world
         .query::<(&mut Transform2D, &mut MoveParams)>()
         .iter()
         .for_each(|(_, (transform, move_params))| {
-            move_params.update(&mut transform.position, d.get_frame_time());
+            move_params.update(&mut transform.position, delta);
         });
 }
 
@@ -438,26 +1512,34 @@ pub fn draw_circle_hitbox(
         });
 }
 
-pub fn rotate_focus(world: &World, d: &RaylibDrawHandle<'_>) {
+pub fn rotate_focus(world: &World, delta: f32) {
     world.query::<&mut Focusable>().iter().for_each(|(_, f)| {
-        f.0 += f.1 * d.get_frame_time();
+        f.0 += f.1 * delta;
     })
 }
 
 pub fn draw_focus(
     world: &World,
     state: &State,
+    input: &ActionState,
+    alpha: f32,
     d: &mut RaylibMode2D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
 ) {
     world
-        .query::<(&Controllable, &Transform2D, &Focusable)>()
+        .query::<(
+            &Controllable,
+            &Transform2D,
+            Option<&PreviousPosition>,
+            &Focusable,
+        )>()
         .iter()
-        .for_each(|(_, (_, t, f))| {
-            if state.controls.is_down(Action::Focus, d) {
+        .for_each(|(_, (_, t, p, f))| {
+            if input.is_down(Action::Focus) {
+                let position = interpolate(t, p, alpha);
                 d.draw_texture_pro(
                     &state.assets.get("commons_sprite"),
                     get_sprite_coord(0, 0, 32., 32.),
-                    Rectangle::new(t.position().re, t.position().im, 32., 32.),
+                    Rectangle::new(position.re, position.im, 32., 32.),
                     Vector2::new(16., 16.),
                     f.0,
                     Color::WHITE,
@@ -495,17 +1577,24 @@ pub fn delete_offscreen(world: &mut World) {
 pub fn draw_boss_bg(
     world: &World,
     state: &State,
+    alpha: f32,
     d: &mut RaylibMode2D<'_, RaylibTextureMode<'_, RaylibDrawHandle<'_>>>,
 ) {
     world
-        .query::<(&Transform2D, &mut RotatingBgBoss, &Boss)>()
+        .query::<(
+            &Transform2D,
+            Option<&PreviousPosition>,
+            &mut RotatingBgBoss,
+            &Boss,
+        )>()
         .iter()
-        .for_each(|(_, (t, b, _))| {
+        .for_each(|(_, (t, p, b, _))| {
+            let position = interpolate(t, p, alpha);
             b.0 += b.1 * d.get_frame_time();
             d.draw_texture_pro(
                 &state.assets.get("commons_sprite"),
                 get_sprite_coord(0, 6, 64., 64.),
-                Rectangle::new(t.position().re, t.position().im, 64., 64.),
+                Rectangle::new(position.re, position.im, 64., 64.),
                 Vector2::new(32., 32.),
                 b.0,
                 Color::WHITE,
@@ -513,7 +1602,7 @@ pub fn draw_boss_bg(
         })
 }
 
-pub fn wanderable_search(world: &World, d: &RaylibDrawHandle<'_>) {
+pub fn wanderable_search(world: &World, rng: &mut Rng, delta: f32) {
     world
         .query::<(&mut MoveParams, &mut Wanderable, &Transform2D)>()
         .iter()
@@ -540,8 +1629,8 @@ pub fn wanderable_search(world: &World, d: &RaylibDrawHandle<'_>) {
                     let width = w.wander_size.width as i32;
                     let y = w.wander_size.y as i32;
                     let height = w.wander_size.height as i32;
-                    let random_x = d.get_random_value::<i32>(x..width) as f32;
-                    let random_y = d.get_random_value::<i32>(y..height) as f32;
+                    let random_x = rng.get_random_value::<i32>(x..width) as f32;
+                    let random_y = rng.get_random_value::<i32>(y..height) as f32;
                     if w.wander_size
                         .check_collision_point_rec(vec2!(random_x, random_y))
                     {
@@ -552,41 +1641,141 @@ pub fn wanderable_search(world: &World, d: &RaylibDrawHandle<'_>) {
                 return;
             }
 
-            w.elapsed += d.get_frame_time();
+            w.elapsed += delta;
         });
 }
 
-pub fn player_control<'a>(world: &mut World, state: &mut State<'a>, d: &RaylibDrawHandle<'_>) {
-    let mut pending: Vec<Box<dyn FnOnce(&mut World, &mut State<'a>)>> = Vec::new();
+pub fn update_homing(world: &mut World, delta: f32) {
+    // INFO : A boss between two phases or going down can't be hit, don't waste shots on it
+    let targets = world
+        .query::<(&Enemy, &Transform2D)>()
+        .without::<&Bullet>()
+        .without::<&PhaseIntro>()
+        .without::<&BossDefeat>()
+        .iter()
+        .map(|(id, (_, transform))| (id, transform.position))
+        .collect::<Vec<_>>();
 
     world
+        .query_mut::<(&mut Homing, &mut Transform2D, &mut MoveParams)>()
+        .into_iter()
+        .for_each(|(_, (homing, transform, movement))| {
+            let position = transform.position;
+            // INFO : Keep the current target while it's alive, else go for the nearest one
+            let target = homing
+                .target
+                .and_then(|target| targets.iter().find(|(id, _)| *id == target))
+                .or_else(|| {
+                    targets.iter().min_by(|(_, a), (_, b)| {
+                        a.distance_squared(&position)
+                            .total_cmp(&b.distance_squared(&position))
+                    })
+                })
+                .copied();
+
+            let Some((id, target)) = target else {
+                homing.target = None;
+                return;
+            };
+            homing.target = Some(id);
+
+            let current = movement.velocity.arg();
+            let mut diff = (target - position).arg() - current;
+            if diff > PI {
+                diff -= TAU;
+            } else if diff < -PI {
+                diff += TAU;
+            }
+            let max_turn = homing.turn_rate * delta;
+            let angle = current + diff.clamp(-max_turn, max_turn);
+
+            movement.velocity = Complex::cdir(angle) * homing.speed;
+            transform.rotation = movement.velocity.rot();
+        });
+}
+
+pub fn update_player_options(sim: &mut Simulation, focused: bool) {
+    let Some((position, attack)) = sim
+        .world
+        .query::<(&Controllable, &Transform2D, &PlayerAttack)>()
+        .iter()
+        .map(|(_, (_, transform, attack))| (transform.position, attack.basic.1))
+        .next()
+    else {
+        return;
+    };
+
+    let count = attack.options(sim.score.power);
+    let options = sim
+        .world
+        .query::<&PlayerOption>()
+        .iter()
+        .map(|(id, option)| (id, option.0))
+        .collect::<Vec<_>>();
+
+    if options.len() != count {
+        options.into_iter().for_each(|(id, _)| {
+            let _ = sim.world.despawn(id);
+        });
+        for index in 0..count {
+            create_player_option(&mut sim.world, position, index);
+        }
+    }
+
+    // INFO : Ease toward the layout so switching focus slide the options instead of teleporting
+    sim.world
+        .query::<(&PlayerOption, &mut Transform2D)>()
+        .iter()
+        .for_each(|(_, (option, transform))| {
+            let target = position + attack.option_offset(option.0, count, focused);
+            transform.position = transform.position.lerp(&target, 0.35);
+            transform.rotation += 6.;
+        });
+}
+
+pub fn player_control(sim: &mut Simulation, input: &ActionState, delta: f32) {
+    let mut pending: Vec<Box<dyn FnOnce(&mut Simulation)>> = Vec::new();
+
+    sim.world
         .query::<(
             &Controllable,
             &Transform2D,
             &mut MoveParams,
             &mut PlayerAttack,
+            Option<&Dying>,
         )>()
         .iter()
-        .for_each(|(_, (_, t, m, a))| {
+        .for_each(|(id, (_, t, m, a, dying))| {
+            // INFO : Frozen in place, only bombing can save the player now
+            if dying.is_some() {
+                m.acceleration = cmpx!(0.);
+                if input.is_pressed(Action::Spell) {
+                    let spells = a.spells.1;
+                    let position = *t.position();
+                    pending.push(Box::new(move |sim| deathbomb(sim, id, spells, position)));
+                }
+                return;
+            }
+
             let mut new_pos = cmpx!(0.);
-            let move_speed = 5000.; // TODO : Make sure this specific to char
-            if state.controls.is_down(Action::Down, d) && t.position().im < 448. - 32. {
+            let move_speed = PLAYER_ACCELERATION; // TODO : Make sure this specific to char
+            if input.is_down(Action::Down) && t.position().im < 448. - 32. {
                 new_pos += cmpx!(0., move_speed);
             }
 
-            if state.controls.is_down(Action::Up, d) && t.position().im > 0. {
+            if input.is_down(Action::Up) && t.position().im > 0. {
                 new_pos += cmpx!(0., -move_speed);
             }
 
-            if state.controls.is_down(Action::Left, d) && t.position().re > 0. {
+            if input.is_down(Action::Left) && t.position().re > 0. {
                 new_pos += cmpx!(-move_speed, 0.);
             }
 
-            if state.controls.is_down(Action::Right, d) && t.position().re < 384. - 32. {
+            if input.is_down(Action::Right) && t.position().re < 384. - 32. {
                 new_pos += cmpx!(move_speed, 0.);
             }
 
-            let move_speed = if state.controls.is_down(Action::Focus, d) {
+            let move_speed = if input.is_down(Action::Focus) {
                 1. / 2.6 // Specific to char
             } else {
                 1.
@@ -594,19 +1783,88 @@ pub fn player_control<'a>(world: &mut World, state: &mut State<'a>, d: &RaylibDr
 
             m.acceleration = new_pos * move_speed;
 
-            if state.controls.is_down(Action::Attack, d) {
-                a.basic.0 .0.update(d.get_frame_time());
-                let action = a.basic.1.spawn(*t.position());
+            if input.is_down(Action::Attack) {
+                a.basic.0 .0.update(delta);
+                let action = a.basic.1.spawn(*t.position(), input.is_down(Action::Focus));
                 pending.push(Box::new(action));
             }
 
-            if state.controls.is_pressed(Action::Spell, d) {
+            if input.is_pressed(Action::Spell) {
                 let action = a.spells.1.spawn(*t.position());
                 pending.push(Box::new(action));
             }
         });
 
     for i in pending {
-        (i)(world, state);
+        (i)(sim);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cmpx, difficulty::Difficulty, pattern::AttackDef, utility::clock::TICK_DELTA};
+
+    fn transform(x: f32, y: f32) -> Transform2D {
+        Transform2D::new(cmpx!(x, y), Vector2::new(1., 1.), 0.)
+    }
+
+    #[test]
+    fn homing_shots_ignore_a_boss_that_cant_be_hit() {
+        let mut world = World::new();
+        let shot = world.spawn((
+            Homing::new(600., 10.),
+            transform(150., 400.),
+            MoveParams::move_linear(cmpx!(0., -600.)),
+        ));
+        let intro = world.spawn((Enemy, PhaseIntro(1.), transform(150., 300.)));
+        let defeat = world.spawn((Enemy, BossDefeat(1.), transform(150., 320.)));
+        let fairy = world.spawn((Enemy, transform(20., 50.)));
+
+        update_homing(&mut world, TICK_DELTA);
+        assert_eq!(world.get::<&Homing>(shot).unwrap().target, Some(fairy));
+
+        // INFO : Once the phase started the boss is the nearest target again
+        let _ = world.remove_one::<PhaseIntro>(intro);
+        world.despawn(defeat).unwrap();
+        world.get::<&mut Homing>(shot).unwrap().target = None;
+        update_homing(&mut world, TICK_DELTA);
+        assert_eq!(world.get::<&Homing>(shot).unwrap().target, Some(intro));
+    }
+
+    #[test]
+    fn scaled_aimed_fan_stay_centred_on_the_player() {
+        for num in [3, 12] {
+            let attack = AttackDef::AtPlayer {
+                num,
+                speed: 150.,
+                spread: 0.2,
+                total_shoot: 1,
+                cooldown: 1.,
+                bullet: toml::from_str(
+                    r#"sheet = "miko_sprite"
+cell = [0, 4]
+hitbox = 2.5"#,
+                )
+                .unwrap(),
+            };
+            for difficulty in [
+                Difficulty::Easy,
+                Difficulty::Normal,
+                Difficulty::Hard,
+                Difficulty::Lunatic,
+            ] {
+                let AttackDef::AtPlayer { num, spread, .. } = attack.scaled(difficulty) else {
+                    unreachable!();
+                };
+                let angles = (0..num)
+                    .map(|i| fan_angle(i, num, spread))
+                    .collect::<Vec<_>>();
+                for (left, right) in angles.iter().zip(angles.iter().rev()) {
+                    assert!((left + right).abs() < 1e-5, "{:?} {:?}", difficulty, angles);
+                }
+                assert_eq!(num % 2 == 1, angles.iter().any(|angle| *angle == 0.));
+            }
+        }
     }
 }
@@ -4,26 +4,22 @@ use std::collections::VecDeque;
 use crate::state::State;
 
 pub struct Dialog {
-    pub player: &'static str,
-    pub opponent: &'static str,
+    pub player: String,
+    pub opponent: String,
 
     pub dialog_list: VecDeque<DialogItem>,
 }
 
 pub enum DialogItem {
-    Player(&'static str),
-    Opponent(&'static str),
+    Player(String),
+    Opponent(String),
 }
 
 impl Dialog {
-    pub fn new(
-        player: &'static str,
-        opponent: &'static str,
-        dialog_list: VecDeque<DialogItem>,
-    ) -> Self {
+    pub fn new(player: &str, opponent: &str, dialog_list: VecDeque<DialogItem>) -> Self {
         Self {
-            player,
-            opponent,
+            player: player.to_owned(),
+            opponent: opponent.to_owned(),
             dialog_list,
         }
     }
@@ -51,8 +47,8 @@ impl Dialog {
 
         let text = match self.dialog_list.front() {
             Some(dialog) => match dialog {
-                DialogItem::Player(a) => a,
-                DialogItem::Opponent(a) => a,
+                DialogItem::Player(a) => a.as_str(),
+                DialogItem::Opponent(a) => a.as_str(),
             },
             None => "",
         };
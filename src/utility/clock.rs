@@ -0,0 +1,37 @@
+pub const TICK_RATE: f32 = 60.;
+pub const TICK_DELTA: f32 = 1. / TICK_RATE;
+
+// INFO : Prevent the simulation from trying to catch up forever after a long hitch
+const MAX_FRAME_TIME: f32 = 0.25;
+
+// INFO : Fixed timestep accumulator, the simulation is stepped by TICK_DELTA no matter how fast
+// the render loop is going
+#[derive(Debug, Clone, Default)]
+pub struct FixedClock {
+    accumulator: f32,
+    pub ticks: u64,
+}
+
+impl FixedClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn accumulate(&mut self, frame_time: f32) {
+        self.accumulator += frame_time.clamp(0., MAX_FRAME_TIME);
+    }
+
+    pub fn tick(&mut self) -> bool {
+        if self.accumulator < TICK_DELTA {
+            return false;
+        }
+        self.accumulator -= TICK_DELTA;
+        self.ticks += 1;
+        true
+    }
+
+    // INFO : How far we are between the last tick and the next one, used for interpolation
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / TICK_DELTA).clamp(0., 1.)
+    }
+}
@@ -1,5 +1,7 @@
 use raylib::prelude::*;
 
+pub mod clock;
+pub mod random;
 pub mod timer;
 
 pub fn get_sprite_coord(row: i32, height: i32, row_size: f32, height_size: f32) -> Rectangle {
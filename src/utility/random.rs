@@ -0,0 +1,64 @@
+use std::{
+    ops::Range,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// INFO : Deterministic random number generator (xorshift64*), raylib's GetRandomValue
+// uses a global state that cannot be restored so it's unusable for replay
+#[derive(Debug, Clone)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // INFO : Scramble the seed with splitmix64 so small seed still give good sequence
+        let mut state = seed.wrapping_add(0x9E3779B97F4A7C15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94D049BB133111EB);
+        state ^= state >> 31;
+        if state == 0 {
+            state = 0x9E3779B97F4A7C15;
+        }
+
+        Self { seed, state }
+    }
+
+    pub fn from_time() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or(0);
+        Self::new(seed)
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545F4914F6CDD1D)
+    }
+
+    // INFO : Uniform in [0, 1), only the top 24 bit since that's all a f32 can hold
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    pub fn range_f32(&mut self, min: f32, max: f32) -> f32 {
+        min + (max - min) * self.next_f32()
+    }
+
+    // INFO : Same behaviour as raylib's get_random_value, the end of the range is inclusive
+    pub fn get_random_value<T: From<i32>>(&mut self, num: Range<i32>) -> T {
+        if num.end <= num.start {
+            return num.start.into();
+        }
+        let span = (num.end as i64 - num.start as i64 + 1) as u64;
+        ((num.start as i64 + (self.next_u64() % span) as i64) as i32).into()
+    }
+}